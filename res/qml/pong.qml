@@ -22,12 +22,15 @@ ApplicationWindow {
       Rectangle {
         property real xx: 0
         property real yy: 0
+        // Paddles are drawn as configured until they are resized
+        property real ww: game.paddleRelativeWidth
+        property real hh: game.paddleRelativeHeight
         x: (xx + 1) * root.width/2 - width/2
         y: (1 - yy) * root.height/2 - height/2
         Behavior on x { NumberAnimation { duration: timer.interval } }
         Behavior on y { NumberAnimation { duration: timer.interval } }
-        width: root.width * game.paddleRelativeWidth
-        height: root.height * game.paddleRelativeHeight
+        width: root.width * ww
+        height: root.height * hh
         color: "#222"
       }
     }
@@ -62,6 +65,18 @@ ApplicationWindow {
       else if(event.key == Qt.Key_Z) game.moveLeftStop();
     }
 
+    // Dragging on either half of the field moves that side's paddle
+    MouseArea {
+      anchors.fill: parent
+      function steer(mouse) {
+        var y = 1 - 2 * mouse.y / height;
+        if(mouse.x < width / 2) game.moveLeftTo(y);
+        else game.moveRightTo(y);
+      }
+      onPressed: steer(mouse)
+      onPositionChanged: steer(mouse)
+    }
+
     Timer {
       id: timer
       interval: 30
@@ -120,6 +135,13 @@ ApplicationWindow {
           e.yy = y;
         }
       }
+      onResizeEntity: function(id, w, h) {
+        var e = entities[id];
+        if(e) {
+          e.ww = w;
+          e.hh = h;
+        }
+      }
       onGoalLeft: function(score) {
         scoreLeft = score;
       }
@@ -0,0 +1,218 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use cgmath::{ Vector2 };
+use toml;
+use super::*;
+
+// Arena files are TOML with an array of obstacle tables, e.g.
+//   [[obstacle]]
+//   position = [0, 6000]
+//   size = [1000, 2000]
+//
+//   [[obstacle]]
+//   position = [-8000, 0]
+//   size = [500, 500]
+//   velocity = [0, 100]
+//   travel = [0, 4000]
+// Sizes are half-extents like in GameConfiguration. Moving obstacles bounce
+// back and forth within `travel` of their position. Obstacles must stay inside
+// the area, out of the paddles' way and clear of the center where balls are served.
+//
+// Breakout level files lay out the bricks row by row from the top, e.g.
+//   rows = [
+//     "3333333333",
+//     "2222222222",
+//     "1.1.1.1.1.",
+//   ]
+// where digits are the hit points of each brick and dots leave gaps. The
+// columns span the width of the area and the rows start a row below the top wall.
+
+#[derive(Debug)]
+pub enum ArenaError {
+    Io(io::Error),
+    Syntax(String),
+    Invalid(String)
+}
+
+// Rows of bricks are a tenth of the area high
+const LEVEL_ROWS_PER_AREA: i64 = 10;
+
+pub fn load<P: AsRef<Path>>(path: P, cfg: &GameConfiguration) -> Result<Vec<ObstacleConfiguration>, ArenaError> {
+    parse(&try!(read(path)), cfg)
+}
+
+pub fn load_level<P: AsRef<Path>>(path: P, cfg: &GameConfiguration) -> Result<Vec<BrickConfiguration>, ArenaError> {
+    parse_level(&try!(read(path)), cfg)
+}
+
+pub fn parse(text: &str, cfg: &GameConfiguration) -> Result<Vec<ObstacleConfiguration>, ArenaError> {
+    let table = try!(parse_toml(text));
+    let obstacles = match table.get("obstacle") {
+        None => { Vec::new() }
+        Some(&toml::Value::Array(ref obstacles)) => {
+            let mut parsed = Vec::new();
+            for (i, obstacle) in obstacles.iter().enumerate() {
+                parsed.push(try!(obstacle_from_toml(obstacle).map_err(|e| invalid(i, &e))));
+            }
+            parsed
+        }
+        Some(_) => { return Err(ArenaError::Invalid("obstacle must be an array of tables".to_string())); }
+    };
+    try!(validate(&obstacles, cfg));
+    Ok(obstacles)
+}
+
+pub fn parse_level(text: &str, cfg: &GameConfiguration) -> Result<Vec<BrickConfiguration>, ArenaError> {
+    let table = try!(parse_toml(text));
+    let rows: Vec<&str> = match table.get("rows") {
+        Some(&toml::Value::Array(ref rows)) => { rows.iter().map(|row| row.as_str()).collect::<Option<_>>().unwrap_or(Vec::new()) }
+        _ => { Vec::new() }
+    };
+    if rows.is_empty() {
+        return Err(ArenaError::Invalid("rows must be a non-empty array of strings".to_string()));
+    }
+
+    if rows[0].is_empty() {
+        return Err(ArenaError::Invalid("row 0: must not be empty".to_string()));
+    }
+
+    let columns = rows[0].chars().count() as i64;
+    let size = Vector2::new(cfg.area.x / columns, cfg.area.y / LEVEL_ROWS_PER_AREA / 2);
+    let mut bricks = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        if row.chars().count() as i64 != columns {
+            return Err(ArenaError::Invalid(format!("row {}: must be as long as the first row", i)));
+        }
+        let y = cfg.area.y - (2 * i as i64 + 3) * size.y;
+        if y - size.y < cfg.ball_size {
+            return Err(ArenaError::Invalid(format!("row {}: must stay clear of the center", i)));
+        }
+        for (j, c) in row.chars().enumerate() {
+            let hit_points = match (c, c.to_digit(10)) {
+                ('.', _) => { continue; }
+                (_, Some(hit_points)) if hit_points > 0 => { hit_points as u8 }
+                _ => { return Err(ArenaError::Invalid(format!("row {}: unknown brick {}", i, c))); }
+            };
+            bricks.push(BrickConfiguration {
+                position: Vector2::new((2 * j as i64 + 1) * size.x - cfg.area.x, y),
+                size: size,
+                hit_points: hit_points
+            });
+        }
+    }
+    if bricks.is_empty() {
+        return Err(ArenaError::Invalid("levels need at least one brick".to_string()));
+    }
+    Ok(bricks)
+}
+
+pub fn validate(obstacles: &[ObstacleConfiguration], cfg: &GameConfiguration) -> Result<(), ArenaError> {
+    let center = Vector2::new(cfg.ball_size, cfg.ball_size);
+    let mode = modes::from_configuration(cfg.mode);
+    for (i, obstacle) in obstacles.iter().enumerate() {
+        if obstacle.size.x <= 0 || obstacle.size.y <= 0 {
+            return Err(invalid(i, "size must be positive"));
+        }
+        if obstacle.travel.x < 0 || obstacle.travel.y < 0 {
+            return Err(invalid(i, "travel must not be negative"));
+        }
+        if (obstacle.velocity.x != 0 && obstacle.travel.x == 0) || (obstacle.velocity.y != 0 && obstacle.travel.y == 0) {
+            return Err(invalid(i, "moving obstacles need travel in their direction"));
+        }
+
+        // Everywhere the obstacle can be while moving
+        let extent = obstacle.size + obstacle.travel;
+        let low = obstacle.position - extent;
+        let high = obstacle.position + extent;
+        if low.x < -cfg.area.x || low.y < -cfg.area.y || high.x > cfg.area.x || high.y > cfg.area.y {
+            return Err(invalid(i, "must stay inside the area"));
+        }
+        if overlaps(low, high, -center, center) {
+            return Err(invalid(i, "must stay clear of the center"));
+        }
+        for player in mode.players() {
+            let paddle = paddle_data(cfg, player, mode.paddle_offset(player));
+            // Paddles sweep their whole zone
+            let edge = paddle.position + forward_vector(paddle.kind) * cfg.paddle_zone.unwrap_or(0);
+            let (line_low, line_high) = (Vector2::new(paddle.position.x.min(edge.x), paddle.position.y.min(edge.y)),
+                                         Vector2::new(paddle.position.x.max(edge.x), paddle.position.y.max(edge.y)));
+            let (lane_low, lane_high) = if is_horizontal_paddle(paddle.kind) {
+                (Vector2::new(-cfg.area.x, line_low.y - paddle.size.y),
+                 Vector2::new(cfg.area.x, line_high.y + paddle.size.y))
+            } else {
+                (Vector2::new(line_low.x - paddle.size.x, -cfg.area.y),
+                 Vector2::new(line_high.x + paddle.size.x, cfg.area.y))
+            };
+            if overlaps(low, high, lane_low, lane_high) {
+                return Err(invalid(i, &format!("must stay out of the way of the {:?} paddle", player)));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read<P: AsRef<Path>>(path: P) -> Result<String, ArenaError> {
+    let mut text = String::new();
+    try!(File::open(path).and_then(|mut file| file.read_to_string(&mut text)).map_err(ArenaError::Io));
+    Ok(text)
+}
+
+fn parse_toml(text: &str) -> Result<toml::Table, ArenaError> {
+    let mut parser = toml::Parser::new(text);
+    match parser.parse() {
+        Some(table) => { Ok(table) }
+        None => {
+            let e = &parser.errors[0];
+            let (line, col) = parser.to_linecol(e.lo);
+            Err(ArenaError::Syntax(format!("{}:{}: {}", line + 1, col + 1, e.desc)))
+        }
+    }
+}
+
+fn obstacle_from_toml(value: &toml::Value) -> Result<ObstacleConfiguration, String> {
+    let table = match value {
+        &toml::Value::Table(ref table) => { table }
+        _ => { return Err("must be a table".to_string()); }
+    };
+    for key in table.keys() {
+        match key.as_str() {
+            "position" | "size" | "velocity" | "travel" => {}
+            _ => { return Err(format!("unknown key {}", key)); }
+        }
+    }
+
+    let vector = |key: &str, default: Option<Vector2<i64>>| {
+        match table.get(key) {
+            Some(value) => { vector_from_toml(value).ok_or(format!("{} must be an array of two integers", key)) }
+            None => { default.ok_or(format!("{} is missing", key)) }
+        }
+    };
+    Ok(ObstacleConfiguration {
+        position: try!(vector("position", None)),
+        size: try!(vector("size", None)),
+        velocity: try!(vector("velocity", Some(Vector2::new(0, 0)))),
+        travel: try!(vector("travel", Some(Vector2::new(0, 0))))
+    })
+}
+
+fn vector_from_toml(value: &toml::Value) -> Option<Vector2<i64>> {
+    match value {
+        &toml::Value::Array(ref array) if array.len() == 2 => {
+            match (&array[0], &array[1]) {
+                (&toml::Value::Integer(x), &toml::Value::Integer(y)) => { Some(Vector2::new(x, y)) }
+                _ => { None }
+            }
+        }
+        _ => { None }
+    }
+}
+
+fn overlaps(low_a: Vector2<i64>, high_a: Vector2<i64>, low_b: Vector2<i64>, high_b: Vector2<i64>) -> bool {
+    low_a.x < high_b.x && low_b.x < high_a.x && low_a.y < high_b.y && low_b.y < high_a.y
+}
+
+fn invalid(obstacle: usize, reason: &str) -> ArenaError {
+    ArenaError::Invalid(format!("obstacle {}: {}", obstacle, reason))
+}
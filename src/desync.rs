@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::hash::Hasher;
+use cgmath::{ Vector2 };
+use super::*;
+
+// 64-bit FNV-1a. Integers are fed in little-endian order so that the same
+// simulation state hashes identically on every platform.
+pub struct StateHasher {
+    hash: u64
+}
+
+impl StateHasher {
+    pub fn new() -> StateHasher {
+        StateHasher { hash: 0xcbf29ce484222325 }
+    }
+
+    pub fn write_vector(&mut self, v: Vector2<i64>) {
+        self.write_i64(v.x);
+        self.write_i64(v.y);
+    }
+}
+
+impl Hasher for StateHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.hash ^= *b as u64;
+            self.hash = self.hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write(&i.to_le_bytes());
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub tick: u64,
+    pub hash: u64,
+    // Peers may exchange only hashes, in which case there is no state to dump
+    pub state: Option<String>
+}
+
+impl Snapshot {
+    pub fn of(game: &Game) -> Snapshot {
+        Snapshot {
+            tick: game.tick(),
+            hash: game.state_hash(),
+            state: Some(game.dump_state())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Desync {
+    pub tick: u64,
+    pub local: Snapshot,
+    pub remote: Snapshot
+}
+
+// Compares snapshots of two simulations that should be in lockstep, such as a
+// game and its peer's copy or a live game and its replay. Snapshots are matched
+// by tick, so both sides only need to record after the same Time actions.
+pub struct DesyncDetector {
+    local: BTreeMap<u64, Snapshot>,
+    remote: BTreeMap<u64, Snapshot>
+}
+
+impl DesyncDetector {
+    pub fn new() -> DesyncDetector {
+        DesyncDetector {
+            local: BTreeMap::new(),
+            remote: BTreeMap::new()
+        }
+    }
+
+    pub fn record_local(&mut self, snapshot: Snapshot) {
+        self.local.insert(snapshot.tick, snapshot);
+    }
+
+    pub fn record_remote(&mut self, snapshot: Snapshot) {
+        self.remote.insert(snapshot.tick, snapshot);
+    }
+
+    // Returns the first tick recorded on both sides with differing hashes.
+    // Ticks that matched are forgotten as they can no longer be the first desync.
+    pub fn check(&mut self) -> Option<Desync> {
+        let common: Vec<u64> = self.local.keys()
+            .filter(|tick| self.remote.contains_key(tick))
+            .cloned()
+            .collect();
+
+        for tick in common {
+            if self.local[&tick].hash != self.remote[&tick].hash {
+                return Some(Desync {
+                    tick: tick,
+                    local: self.local[&tick].clone(),
+                    remote: self.remote[&tick].clone()
+                });
+            }
+            self.local.remove(&tick);
+            self.remote.remove(&tick);
+        }
+        None
+    }
+}
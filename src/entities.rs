@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+use cgmath::{ Vector2 };
+use super::{ Entity, ID };
+
+// Components of a single entity. Every entity is an axis-aligned box whose
+// size holds its half-extents, like the sizes in GameConfiguration.
+#[derive(Clone, Debug)]
+pub struct EntityData {
+    pub kind: Entity,
+    pub size: Vector2<i64>,
+    pub position: Vector2<i64>,
+    pub velocity: Vector2<i64>
+}
+
+impl EntityData {
+    pub fn overlaps(&self, other: &EntityData) -> bool {
+        let diff = self.position - other.position;
+        diff.x.abs() < self.size.x + other.size.x
+            && diff.y.abs() < self.size.y + other.size.y
+    }
+}
+
+// IDs are allocated in increasing order and never reused, so iteration follows
+// creation order.
+#[derive(Debug)]
+pub struct EntityStore {
+    next_id: ID,
+    entities: BTreeMap<ID, EntityData>
+}
+
+impl EntityStore {
+    pub fn new() -> EntityStore {
+        EntityStore {
+            next_id: 0,
+            entities: BTreeMap::new()
+        }
+    }
+
+    pub fn insert(&mut self, data: EntityData) -> ID {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entities.insert(id, data);
+        id
+    }
+
+    pub fn remove(&mut self, id: ID) -> Option<EntityData> {
+        self.entities.remove(&id)
+    }
+
+    pub fn get(&self, id: ID) -> Option<&EntityData> {
+        self.entities.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: ID) -> Option<&mut EntityData> {
+        self.entities.get_mut(&id)
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<ID, EntityData> {
+        self.entities.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> btree_map::IterMut<ID, EntityData> {
+        self.entities.iter_mut()
+    }
+
+    pub fn ids(&self, kind: Entity) -> Vec<ID> {
+        self.entities.iter()
+            .filter(|&(_, e)| e.kind == kind)
+            .map(|(&id, _)| id)
+            .collect()
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::time::{ Duration, Instant };
+use super::*;
+
+// Guards a Game against its remote peers. The first peer to join becomes the
+// host, peers are given the game's players in order as they join and any
+// further peers are spectators. Only the host may drive the game with
+// Initialize, Start, Reset and Time.
+
+pub type PeerID = u32;
+
+// How far one Time can advance a running game, and how far from the game's
+// time inputs may be, in microseconds
+const MAX_TIME_STEP: u64 = 1000000;
+const INPUT_WINDOW: u64 = 1000000;
+
+struct Peer {
+    player: Option<Player>,
+    window_start: Instant,
+    inputs: u32
+}
+
+pub struct Host {
+    game: Game,
+    peers: BTreeMap<PeerID, Peer>,
+    max_inputs_per_second: u32,
+    // The last Time the game took, which can be ahead of its last whole tick
+    last_t: u64
+}
+
+impl Host {
+    pub fn new(cfg: GameConfiguration, max_inputs_per_second: u32) -> Host {
+        Host {
+            game: Game::new(cfg),
+            peers: BTreeMap::new(),
+            max_inputs_per_second: max_inputs_per_second,
+            last_t: 0
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn join(&mut self, peer: PeerID) -> Option<Player> {
+        let player = self.game.players().into_iter()
+            .find(|&player| !self.peers.values().any(|p| p.player == Some(player)));
+        self.peers.insert(peer, Peer {
+            player: player,
+            window_start: Instant::now(),
+            inputs: 0
+        });
+        player
+    }
+
+    pub fn leave(&mut self, peer: PeerID) {
+        self.peers.remove(&peer);
+    }
+
+    pub fn is_host(&self, peer: PeerID) -> bool {
+        self.peers.keys().next() == Some(&peer)
+    }
+
+    pub fn process<F>(&mut self, peer: PeerID, action: Action, callback: F) -> Result<(), Error> where F: FnMut(Event) {
+        try!(self.validate(peer, &action));
+        let time = match action {
+            Action::Time { t } => { Some(t) }
+            _ => { None }
+        };
+        try!(self.game.process(action, callback));
+        if let Some(t) = time {
+            self.last_t = t;
+        }
+        Ok(())
+    }
+
+    fn validate(&mut self, peer: PeerID, action: &Action) -> Result<(), Error> {
+        let is_host = self.is_host(peer);
+        let max_inputs = self.max_inputs_per_second;
+        let (game_t, running) = (self.game.time(), self.game.state() == State::Running);
+        let last_t = self.last_t.max(game_t);
+        let p = match self.peers.get_mut(&peer) {
+            Some(p) => { p }
+            None => {
+                warn!("Rejected action from unknown peer {}", peer);
+                return Err(Error::UnknownPeer);
+            }
+        };
+
+        match action {
+            &Action::Move { t, player, .. } | &Action::MoveAxis { t, player, .. } | &Action::MoveTo { t, player, .. } |
+            &Action::Ability { t, player } => {
+                if p.player != Some(player) {
+                    warn!("Peer {} tried to move {:?} paddle while controlling {:?}", peer, player, p.player);
+                    return Err(Error::WrongPlayer);
+                }
+                if t.saturating_add(INPUT_WINDOW) < game_t || t > game_t.saturating_add(INPUT_WINDOW) {
+                    warn!("Peer {} sent an input for {} at game time {}", peer, t, game_t);
+                    return Err(Error::TimeOutOfRange);
+                }
+
+                let now = Instant::now();
+                if now.duration_since(p.window_start) >= Duration::from_secs(1) {
+                    p.window_start = now;
+                    p.inputs = 0;
+                }
+                p.inputs += 1;
+                if p.inputs > max_inputs {
+                    warn!("Peer {} exceeded {} inputs per second", peer, max_inputs);
+                    return Err(Error::RateLimited);
+                }
+                Ok(())
+            }
+            _ => {
+                if !is_host {
+                    warn!("Peer {} tried to control the game without being the host", peer);
+                    return Err(Error::NotHost);
+                }
+                // Time never goes back, and a running game cannot skip too far ahead at once
+                if let &Action::Time { t } = action {
+                    if t < last_t || (running && t - last_t > MAX_TIME_STEP) {
+                        warn!("Peer {} tried to set the time to {} after {}", peer, t, last_t);
+                        return Err(Error::TimeOutOfRange);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
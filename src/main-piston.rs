@@ -12,7 +12,7 @@ use piston::window::WindowSettings;
 use glutin_window::GlutinWindow as Window;
 use opengl_graphics::{ GlGraphics, OpenGL };
 use piston::event_loop::*;
-use piston::input::{UpdateArgs, RenderArgs, Key, Button, RenderEvent, UpdateEvent, PressEvent, ReleaseEvent};
+use piston::input::{UpdateArgs, RenderArgs, Key, Button, RenderEvent, UpdateEvent, PressEvent, ReleaseEvent, MouseCursorEvent};
 use opengl_graphics::glyph_cache::GlyphCache;
 use std::path::Path;
 use cgmath::{ Vector2 };
@@ -23,6 +23,10 @@ const FONT_PATH: &'static str = "res/ttf/DejaVuSans.ttf";
 const BACKGROUND_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
 const PADDLE_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
 const BALL_COLOR: [f32; 4] = [0.9, 0.9, 0.9, 1.0];
+const POWER_UP_COLOR: [f32; 4] = [0.2, 0.7, 0.3, 1.0];
+const OBSTACLE_COLOR: [f32; 4] = [0.4, 0.4, 0.5, 1.0];
+const WALL_COLOR: [f32; 4] = [0.4, 0.4, 0.5, 1.0];
+const BRICK_COLOR: [f32; 4] = [0.7, 0.4, 0.2, 1.0];
 const TEXT_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
 
 const WINDOW_SIZE: Vector2<i64> = Vector2 {
@@ -44,9 +48,94 @@ const CFG: GameConfiguration = GameConfiguration {
         x: WINDOW_SIZE.x * GAME_WINDOW_RATIO.x / 100,
         y: WINDOW_SIZE.y * GAME_WINDOW_RATIO.y / 16
     },
-    ball_size: WINDOW_SIZE.x * GAME_WINDOW_RATIO.x / 100
+    ball_size: WINDOW_SIZE.x * GAME_WINDOW_RATIO.x / 100,
+    goal_opening: None,
+    walls: REFLECTING_WALLS,
+    mode: Mode::Classic,
+    multiball: None,
+    power_ups: None,
+    field: None,
+    paddle_physics: None,
+    paddle_zone: None,
+    abilities: Vec::new(),
+    handicaps: Vec::new(),
+    difficulty: None,
+    tennis: None,
+    obstacles: Vec::new(),
+    bricks: Vec::new()
 };
 
+const POWER_UPS: PowerUpConfiguration = PowerUpConfiguration {
+    interval: 5000,
+    duration: 8000,
+    max_power_ups: 2,
+    size: CFG.ball_size * 2
+};
+
+const FIELD: FieldConfiguration = FieldConfiguration {
+    gravity: Vector2 { x: 0, y: -100 },
+    wind: Some(WindConfiguration {
+        force: Vector2 { x: 60, y: 0 },
+        period: 4000,
+        duration: 1500
+    }),
+    curve: 500
+};
+
+const PADDLE_PHYSICS: PaddlePhysicsConfiguration = PaddlePhysicsConfiguration {
+    acceleration: 30,
+    max_speed: 450,
+    friction: 20
+};
+
+const DIFFICULTY: DifficultyConfiguration = DifficultyConfiguration {
+    shrink: 50,
+    rubber_band: 100,
+    min_scale: 500,
+    max_scale: 1500
+};
+
+const HOCKEY_GOAL_OPENING: i64 = CFG.area.y / 3;
+const FOUR_PLAYER_LIVES: u8 = 3;
+const PRACTICE_SERVE_SPEED: i64 = 300;
+const BREAKOUT_LIVES: u8 = 3;
+const MATCH_DURATION: u64 = 120000000;
+const TENNIS: TennisConfiguration = TennisConfiguration {
+    games: 6,
+    sets: 2,
+    tiebreak_points: 7
+};
+const PADDLE_ZONE: i64 = CFG.area.x / 4;
+const DOUBLES_FORWARD_OFFSET: i64 = CFG.area.x / 2;
+const ABILITY_COOLDOWN: u64 = 5000;
+const ABILITY_DURATION: u64 = 500;
+
+// Keys moving each paddle Up and Down, horizontal paddles move right on Up
+const PADDLE_KEYS: [(Player, Key, Key); 6] = [
+    (Player::Left, Key::A, Key::Z),
+    (Player::Right, Key::Up, Key::Down),
+    (Player::Top, Key::M, Key::N),
+    (Player::Bottom, Key::Right, Key::Left),
+    (Player::LeftForward, Key::S, Key::X),
+    (Player::RightForward, Key::O, Key::L)
+];
+
+// Keys moving the side paddles Forward and Back within their zone
+const ZONE_KEYS: [(Player, Key, Key); 2] = [
+    (Player::Left, Key::W, Key::Q),
+    (Player::Right, Key::J, Key::K)
+];
+
+// Keys using each player's ability
+const ABILITY_KEYS: [(Player, Key); 6] = [
+    (Player::Left, Key::E),
+    (Player::Right, Key::RShift),
+    (Player::Top, Key::B),
+    (Player::Bottom, Key::RCtrl),
+    (Player::LeftForward, Key::D),
+    (Player::RightForward, Key::I)
+];
+
 struct Sprite {
     entity: Entity,
     pos: Vector2<f64>
@@ -56,32 +145,77 @@ pub struct App<'a> {
     gl: GlGraphics,
     glyph_cache: GlyphCache<'a>,
     sprites: HashMap<u64, Sprite>,
-    left_score: u8,
-    right_score: u8,
+    // Number of active InvisibleBall power-ups
+    invisible_balls: u32,
+    // Seconds left in a timed match
+    clock: Option<u64>,
+    held_keys: Vec<Key>,
     t: u64,
 }
 
 impl <'a>App<'a> {
-    fn render(&mut self, args: &RenderArgs) {
+    fn render(&mut self, args: &RenderArgs, game: &Game) {
         use graphics::*;
         let gl = &mut self.gl;
         let sprites = &self.sprites;
+        let invisible_balls = self.invisible_balls > 0;
         let character_cache = &mut self.glyph_cache;
-        let left_score_string = self.left_score.to_string();
-        let right_score_string = self.right_score.to_string();
+        let tennis = game.configuration().tennis.is_some();
+        let lives = match game.configuration().mode {
+            Mode::FourPlayer { .. } | Mode::Breakout { .. } => { true }
+            _ => { false }
+        };
+        // Teammates share a score, so only sides get a label
+        let sides = game.players().into_iter().filter(|player| player.side() == *player);
+        let mut labels: Vec<(String, f64, f64)> = sides.map(|player| {
+            // Tennis shows sets, games and the call of the points
+            let value = if lives {
+                game.lives(player).to_string()
+            } else if tennis {
+                format!("{} {} {}", game.sets(player), game.games(player), game.call(player))
+            } else {
+                game.score(player).to_string()
+            };
+            let (x, y) = match player {
+                Player::Left => { (5.0, 20.0) }
+                Player::Right => { (WINDOW_SIZE.x as f64 - 8.0 - 12.0 * value.len() as f64, 20.0) }
+                Player::Top => { (WINDOW_SIZE.x as f64 / 2.0 - 5.0, 20.0) }
+                _ => { (WINDOW_SIZE.x as f64 / 2.0 - 5.0, WINDOW_SIZE.y as f64 - 5.0) }
+            };
+            (value, x, y)
+        }).collect();
+        if let Some(seconds) = self.clock {
+            labels.push((format!("{}:{:02}", seconds / 60, seconds % 60), WINDOW_SIZE.x as f64 / 2.0 - 20.0, 20.0));
+        }
+        // End walls around the goal openings, as (x, y, width, height)
+        let walls: Vec<[f64; 4]> = match game.configuration().goal_opening {
+            Some(opening) => {
+                let length = (WINDOW_SIZE.y / 2 - opening / GAME_WINDOW_RATIO.y) as f64;
+                let (width, height) = (WINDOW_SIZE.x as f64, WINDOW_SIZE.y as f64);
+                vec![[0.0, 0.0, 2.0, length], [0.0, height - length, 2.0, length],
+                     [width - 2.0, 0.0, 2.0, length], [width - 2.0, height - length, 2.0, length]]
+            }
+            None => { Vec::new() }
+        };
 
         gl.draw(args.viewport(), |c, gl| {
             clear(BACKGROUND_COLOR, gl);
 
-            for (_, s) in sprites {
-               s.draw(&c, gl); 
+            for wall in walls.iter() {
+                rectangle(WALL_COLOR, *wall, c.transform, gl);
+            }
+
+            for (&id, s) in sprites {
+                if !(invisible_balls && s.entity == Entity::Ball) {
+                    s.draw(game.entity(id).unwrap().size, &c, gl);
+                }
             }
 
             let text = text::Text::new_color(TEXT_COLOR, 20);
-            text.draw(&left_score_string, character_cache,
-                      &c.draw_state, c.transform.trans(5.0, 20.0), gl);
-            text.draw(&right_score_string, character_cache,
-                      &c.draw_state, c.transform.trans(WINDOW_SIZE.x as f64 - 20.0, 20.0), gl);
+            for &(ref label, x, y) in labels.iter() {
+                text.draw(label, character_cache,
+                          &c.draw_state, c.transform.trans(x, y), gl);
+            }
         });
 
     }
@@ -91,68 +225,76 @@ impl <'a>App<'a> {
         game.process(Action::Time{t: self.t}, |e| self.handle_event(e)).unwrap();
     }
 
+    // The mouse steers the first player's paddle to the cursor
+    fn point(&mut self, pos: [f64; 2], game: &mut Game) {
+        let player = game.players()[0];
+        let x = (pos[0] as i64 - WINDOW_SIZE.x / 2) * GAME_WINDOW_RATIO.x;
+        let y = (WINDOW_SIZE.y / 2 - pos[1] as i64) * GAME_WINDOW_RATIO.y;
+        let target = match player {
+            Player::Top | Player::Bottom => { x }
+            _ => { y }
+        };
+        game.process(Action::MoveTo { t: self.t, player: player, y: target },
+            |e| self.handle_event(e)).unwrap();
+    }
+
     fn control(&mut self, button: Button, pressed: bool, game: &mut Game) {
+        let key = match button {
+            Button::Keyboard(key) => { key }
+            _ => { return; }
+        };
+
         if pressed {
-            match button {
-        
-                Button::Keyboard(Key::Up) => {
-                    game.process(Action::Move {
-                        player: Player::Right,
-                        direction: Direction::Up },
-                        |e| self.handle_event(e)).unwrap();
-                }
-                Button::Keyboard(Key::Down) => {
-                    game.process(Action::Move {
-                        player: Player::Right,
-                        direction: Direction::Down },
-                        |e| self.handle_event(e)).unwrap();
-                }
-                Button::Keyboard(Key::A) => {
-                    game.process(Action::Move {
-                        player: Player::Left,
-                        direction: Direction::Up },
-                        |e| self.handle_event(e)).unwrap();
-                }
-                Button::Keyboard(Key::Z) => {
-                    game.process(Action::Move {
-                        player: Player::Left,
-                        direction: Direction::Down },
-                        |e| self.handle_event(e)).unwrap();
-                }
-                Button::Keyboard(Key::R) => {
-                    game.process(Action::Reset { seed: 0 },
-                        |e| self.handle_event(e)).unwrap();
-                }
-                Button::Keyboard(Key::Space) => {
-                    game.process(Action::Start,
-                        |e| self.handle_event(e)).unwrap();
-                }
-                _ => ()
+            if !self.held_keys.contains(&key) {
+                self.held_keys.push(key);
             }
         } else {
-            match button {
-                Button::Keyboard(Key::Up) => {
-                    game.process(Action::Move {
-                        player: Player::Right,
-                        direction: Direction::Neutral },
-                        |e| self.handle_event(e)).unwrap();
-                }
-                Button::Keyboard(Key::Down) => {
-                    game.process(Action::Move {
-                        player: Player::Right,
-                        direction: Direction::Neutral },
+            self.held_keys.retain(|&held| held != key);
+        }
+
+        for &(player, up, down) in PADDLE_KEYS.iter() {
+            let (forward, back) = match ZONE_KEYS.iter().find(|keys| keys.0 == player) {
+                Some(&(_, forward, back)) => { (Some(forward), Some(back)) }
+                None => { (None, None) }
+            };
+            if [Some(up), Some(down), forward, back].contains(&Some(key)) && game.players().contains(&player) {
+                let direction = {
+                    let held = |key: Option<Key>| key.map(|key| self.held_keys.contains(&key)).unwrap_or(false);
+                    let along = held(Some(up)) as i8 - held(Some(down)) as i8;
+                    match (along, held(forward) as i8 - held(back) as i8) {
+                        (1, 1) => { Direction::UpForward }
+                        (1, -1) => { Direction::UpBack }
+                        (1, _) => { Direction::Up }
+                        (-1, 1) => { Direction::DownForward }
+                        (-1, -1) => { Direction::DownBack }
+                        (-1, _) => { Direction::Down }
+                        (_, 1) => { Direction::Forward }
+                        (_, -1) => { Direction::Back }
+                        _ => { Direction::Neutral }
+                    }
+                };
+                game.process(Action::Move {
+                    t: self.t,
+                    player: player,
+                    direction: direction },
+                    |e| self.handle_event(e)).unwrap();
+            }
+        }
+
+        if pressed {
+            for &(player, ability) in ABILITY_KEYS.iter() {
+                if key == ability && game.players().contains(&player) {
+                    game.process(Action::Ability { t: self.t, player: player },
                         |e| self.handle_event(e)).unwrap();
                 }
-                Button::Keyboard(Key::A) => {
-                    game.process(Action::Move {
-                        player: Player::Left,
-                        direction: Direction::Neutral },
+            }
+            match key {
+                Key::R => {
+                    game.process(Action::Reset { seed: 0 },
                         |e| self.handle_event(e)).unwrap();
                 }
-                Button::Keyboard(Key::Z) => {
-                    game.process(Action::Move {
-                        player: Player::Left,
-                        direction: Direction::Neutral },
+                Key::Space => {
+                    game.process(Action::Start,
                         |e| self.handle_event(e)).unwrap();
                 }
                 _ => ()
@@ -180,15 +322,22 @@ impl <'a>App<'a> {
                 let s = self.sprites.get_mut(&id).unwrap();
                 s.pos = to_screen_pos(x, y);
             }
-            Event::Goal { player, score } => {
-                match player {
-                    Player::Left => { self.left_score = score; }
-                    Player::Right => { self.right_score = score; }
-                };
+            Event::PowerUpCollected { power_up: PowerUp::InvisibleBall, .. } => {
+                self.invisible_balls += 1;
+            }
+            Event::PowerUpExpired { power_up: PowerUp::InvisibleBall, .. } => {
+                self.invisible_balls -= 1;
+            }
+            Event::ClockTick { remaining } => {
+                self.clock = Some((remaining + 999999) / 1000000);
+            }
+            Event::Goal { .. } | Event::LifeLost { .. } | Event::StreakEnded { .. } | Event::BrickDestroyed { .. }
+                | Event::PowerUpSpawned { .. } | Event::PowerUpCollected { .. } | Event::PowerUpExpired { .. }
+                | Event::AbilityUsed { .. } | Event::Resize { .. } => {
+
             }
             Event::Reset => {
-                self.left_score = 0;
-                self.right_score = 0;
+
             }
             Event::RoundStart => {
 
@@ -213,18 +362,102 @@ fn main() {
             gl: GlGraphics::new(opengl),
             glyph_cache: glyph_cache,
             sprites: HashMap::new(),
-            left_score: 0,
-            right_score: 0,
+            invisible_balls: 0,
+            clock: None,
+            held_keys: Vec::new(),
             t: 0
         };
 
-        let mut game = Game::new(CFG.clone());
+        let mut cfg = if std::env::args().any(|arg| arg == "--four-player") {
+            GameConfiguration { mode: Mode::FourPlayer { lives: FOUR_PLAYER_LIVES }, ..CFG.clone() }
+        } else if std::env::args().any(|arg| arg == "--doubles") {
+            GameConfiguration { mode: Mode::Doubles { forward_offset: DOUBLES_FORWARD_OFFSET }, ..CFG.clone() }
+        } else if std::env::args().any(|arg| arg == "--breakout") {
+            GameConfiguration { mode: Mode::Breakout { lives: BREAKOUT_LIVES }, ..CFG.clone() }
+        } else if std::env::args().any(|arg| arg == "--squash") {
+            GameConfiguration { mode: Mode::Squash, ..CFG.clone() }
+        } else if std::env::args().any(|arg| arg == "--timed") {
+            GameConfiguration { mode: Mode::Timed { duration: MATCH_DURATION }, ..CFG.clone() }
+        } else if std::env::args().any(|arg| arg == "--practice") {
+            let mode = Mode::Practice { pattern: ServePattern::Random, speed: PRACTICE_SERVE_SPEED };
+            GameConfiguration { mode: mode, ..CFG.clone() }
+        } else {
+            CFG.clone()
+        };
+        if std::env::args().any(|arg| arg == "--wrap") {
+            cfg.walls.top = WallBehaviour::Wrap;
+            cfg.walls.bottom = WallBehaviour::Wrap;
+        } else if std::env::args().any(|arg| arg == "--absorb") {
+            cfg.walls.top = WallBehaviour::Absorb;
+            cfg.walls.bottom = WallBehaviour::Absorb;
+        }
+        if std::env::args().any(|arg| arg == "--hockey") {
+            cfg.goal_opening = Some(HOCKEY_GOAL_OPENING);
+        }
+        if std::env::args().any(|arg| arg == "--field") {
+            cfg.field = Some(FIELD);
+        }
+        if std::env::args().any(|arg| arg == "--zone") {
+            cfg.paddle_zone = Some(PADDLE_ZONE);
+        }
+        if std::env::args().any(|arg| arg == "--inertia") {
+            cfg.paddle_physics = Some(PADDLE_PHYSICS);
+        }
+        if std::env::args().any(|arg| arg == "--tennis") {
+            cfg.tennis = Some(TENNIS);
+        }
+        if std::env::args().any(|arg| arg == "--difficulty") {
+            cfg.difficulty = Some(DIFFICULTY);
+        }
+        if std::env::args().any(|arg| arg == "--power-ups") {
+            cfg.power_ups = Some(POWER_UPS);
+        }
+        if let Some(i) = std::env::args().position(|arg| arg == "--ability") {
+            let ability = match std::env::args().nth(i + 1).as_ref().map(|name| &name[..]) {
+                Some("dash") => { Ability::Dash }
+                Some("catch") => { Ability::Catch }
+                Some("smash") => { Ability::Smash }
+                _ => {
+                    println!("Expected dash, catch or smash after --ability");
+                    return;
+                }
+            };
+            let players = Game::new(cfg.clone()).players();
+            cfg.abilities = players.into_iter().map(|player| AbilityConfiguration {
+                player: player,
+                ability: ability,
+                cooldown: ABILITY_COOLDOWN,
+                duration: ABILITY_DURATION
+            }).collect();
+        }
+        if let Some(i) = std::env::args().position(|arg| arg == "--breakout") {
+            let path = std::env::args().nth(i + 1).unwrap_or(String::new());
+            match arena::load_level(&path, &cfg) {
+                Ok(bricks) => { cfg.bricks = bricks; }
+                Err(e) => {
+                    println!("Could not load level {}: {:?}", path, e);
+                    return;
+                }
+            }
+        }
+        if let Some(i) = std::env::args().position(|arg| arg == "--arena") {
+            let path = std::env::args().nth(i + 1).unwrap_or(String::new());
+            match arena::load(&path, &cfg) {
+                Ok(obstacles) => { cfg.obstacles = obstacles; }
+                Err(e) => {
+                    println!("Could not load arena {}: {:?}", path, e);
+                    return;
+                }
+            }
+        }
+        let mut game = Game::new(cfg);
         game.process(Action::Initialize, |e| app.handle_event(e)).unwrap();
+        let mouse = std::env::args().any(|arg| arg == "--mouse");
 
         let mut events = window.events();
         while let Some(e) = events.next(&mut window) {
             if let Some(r) = e.render_args() {
-                app.render(&r);
+                app.render(&r, &game);
             }
 
             else if let Some(u) = e.update_args() {
@@ -238,6 +471,12 @@ fn main() {
             else if let Some(button) = e.release_args() {
                 app.control(button, false, &mut game);
             }
+
+            else if let Some(pos) = e.mouse_cursor_args() {
+                if mouse {
+                    app.point(pos, &mut game);
+                }
+            }
         }
     } else {
         println!("Could not load font at {}", FONT_PATH);
@@ -245,7 +484,8 @@ fn main() {
 }
 
 impl Sprite {
-    fn draw(&self, c: &graphics::Context, gl: &mut GlGraphics) {
+    // Balls and power-ups are drawn as configured, the rest at their own size
+    fn draw(&self, size: Vector2<i64>, c: &graphics::Context, gl: &mut GlGraphics) {
         use graphics::*;
         match self.entity {
             
@@ -254,12 +494,29 @@ impl Sprite {
                                                       (CFG.ball_size/1000) as f64);
                 rectangle(BALL_COLOR, rect, c.transform, gl);
             }
-            Entity::LeftPaddle | Entity::RightPaddle => {
+            Entity::LeftPaddle | Entity::RightPaddle | Entity::TopPaddle | Entity::BottomPaddle => {
                 let rect = rectangle::centered([self.pos.x, self.pos.y,
-                                               (CFG.paddle.x/1000) as f64,
-                                               (CFG.paddle.y/1000) as f64]);
+                                               (size.x/1000) as f64,
+                                               (size.y/1000) as f64]);
                 rectangle(PADDLE_COLOR, rect, c.transform, gl);
             }
+            Entity::PowerUp => {
+                let rect = rectangle::centered_square(self.pos.x, self.pos.y,
+                                                      (POWER_UPS.size/1000) as f64);
+                rectangle(POWER_UP_COLOR, rect, c.transform, gl);
+            }
+            Entity::Obstacle => {
+                let rect = rectangle::centered([self.pos.x, self.pos.y,
+                                               (size.x/1000) as f64,
+                                               (size.y/1000) as f64]);
+                rectangle(OBSTACLE_COLOR, rect, c.transform, gl);
+            }
+            Entity::Brick => {
+                let rect = rectangle::centered([self.pos.x, self.pos.y,
+                                               (size.x/1000) as f64 - 1.0,
+                                               (size.y/1000) as f64 - 1.0]);
+                rectangle(BRICK_COLOR, rect, c.transform, gl);
+            }
         };
     }
 }
@@ -19,16 +19,31 @@ const CFG: GameConfiguration = GameConfiguration {
         x: 10000,
         y: 50000,
     },
-    ball_size: 10000
+    ball_size: 10000,
+    goal_opening: None,
+    walls: REFLECTING_WALLS,
+    mode: Mode::Classic,
+    multiball: None,
+    power_ups: None,
+    field: None,
+    paddle_physics: None,
+    paddle_zone: None,
+    abilities: Vec::new(),
+    handicaps: Vec::new(),
+    difficulty: None,
+    tennis: None,
+    obstacles: Vec::new(),
+    bricks: Vec::new()
 };
 
 pub struct Pong {
-    game: Game
+    game: Game,
+    t: u64
 }
 
 impl Default for Pong {
     fn default() -> Self {
-        Pong { game: Game::new(CFG.clone()) }
+        Pong { game: Game::new(CFG.clone()), t: 0 }
     }
 }
 
@@ -41,6 +56,7 @@ pub Pong as QPong {
         fn createBall(id: i32, x: f64, y: f64);
         fn destroyEntity(id: i32);
         fn moveEntity(id: i32, x: f64, y: f64);
+        fn resizeEntity(id: i32, w: f64, h: f64);
         fn goalLeft(score: i32);
         fn goalRight(score: i32);
         fn reseted();
@@ -57,6 +73,9 @@ pub Pong as QPong {
         fn moveRightUp();
         fn moveRightDown();
         fn moveRightStop();
+        // Relative y of the pointer
+        fn moveLeftTo(y: f64);
+        fn moveRightTo(y: f64);
     properties:
         paddleRelativeWidth: f64; read: get_paddle_relative_width, write: set_paddle_relative_width, notify: paddle_relative_width_changed;
         paddleRelativeHeight: f64; read: get_paddle_relative_height, write: set_paddle_relative_height, notify: paddle_relative_height_changed;
@@ -79,6 +98,8 @@ impl QPong {
                     Entity::LeftPaddle => { self.createLeft(id as i32, to_rel_x(x), to_rel_y(y)) }
                     Entity::RightPaddle => { self.createRight(id as i32, to_rel_x(x), to_rel_y(y)) }
                     Entity::Ball => { self.createBall(id as i32, to_rel_x(x), to_rel_y(y)) }
+                    // The QML frontend only plays the classic mode without power-ups or obstacles
+                    Entity::TopPaddle | Entity::BottomPaddle | Entity::PowerUp | Entity::Obstacle | Entity::Brick => {}
                 };
             }
             Event::Destroy { id } => {
@@ -87,12 +108,19 @@ impl QPong {
             Event::Move { id, x, y } => {
                 self.moveEntity(id as i32, to_rel_x(x), to_rel_y(y));
             }
+            Event::Resize { id, w, h } => {
+                self.resizeEntity(id as i32, to_rel_x(w), to_rel_y(h));
+            }
             Event::Goal { player, score } => {
                 match player {
-                    Player::Left => { self.goalLeft(score as i32); }
-                    Player::Right => { self.goalRight(score as i32); }
+                    Player::Left => { self.goalLeft(score.points as i32); }
+                    Player::Right => { self.goalRight(score.points as i32); }
+                    _ => {}
                 };
             }
+            Event::LifeLost { .. } | Event::StreakEnded { .. } | Event::BrickDestroyed { .. }
+                | Event::PowerUpSpawned { .. } | Event::PowerUpCollected { .. } | Event::PowerUpExpired { .. }
+                | Event::AbilityUsed { .. } | Event::ClockTick { .. } => {}
             Event::Reset => {
                 self.reseted();
             }
@@ -128,31 +156,40 @@ impl QPong {
         None
     }
     fn time(&mut self, t: i32) -> Option<&QVariant> {
-        self.process(Action::Time{t: t as u64});
+        self.t = t as u64;
+        self.process(Action::Time{t: self.t});
         None
     }
     fn moveLeftUp(&mut self) -> Option<&QVariant> {
-        self.process(Action::Move{player: Player::Left, direction: Direction::Up});
+        self.process(Action::Move{t: self.t, player: Player::Left, direction: Direction::Up});
         None
     }
     fn moveLeftDown(&mut self) -> Option<&QVariant> {
-        self.process(Action::Move{player: Player::Left, direction: Direction::Down});
+        self.process(Action::Move{t: self.t, player: Player::Left, direction: Direction::Down});
         None
     }
     fn moveLeftStop(&mut self) -> Option<&QVariant> {
-        self.process(Action::Move{player: Player::Left, direction: Direction::Neutral});
+        self.process(Action::Move{t: self.t, player: Player::Left, direction: Direction::Neutral});
         None
     }
     fn moveRightUp(&mut self) -> Option<&QVariant> {
-        self.process(Action::Move{player: Player::Right, direction: Direction::Up});
+        self.process(Action::Move{t: self.t, player: Player::Right, direction: Direction::Up});
         None
     }
     fn moveRightDown(&mut self) -> Option<&QVariant> {
-        self.process(Action::Move{player: Player::Right, direction: Direction::Down});
+        self.process(Action::Move{t: self.t, player: Player::Right, direction: Direction::Down});
         None
     }
     fn moveRightStop(&mut self) -> Option<&QVariant> {
-        self.process(Action::Move{player: Player::Right, direction: Direction::Neutral});
+        self.process(Action::Move{t: self.t, player: Player::Right, direction: Direction::Neutral});
+        None
+    }
+    fn moveLeftTo(&mut self, y: f64) -> Option<&QVariant> {
+        self.process(Action::MoveTo{t: self.t, player: Player::Left, y: (y * CFG.area.y as f64) as i64});
+        None
+    }
+    fn moveRightTo(&mut self, y: f64) -> Option<&QVariant> {
+        self.process(Action::MoveTo{t: self.t, player: Player::Right, y: (y * CFG.area.y as f64) as i64});
         None
     }
 }
@@ -0,0 +1,42 @@
+extern crate pong;
+extern crate cgmath;
+extern crate env_logger;
+
+use cgmath::{ Vector2 };
+use pong::*;
+
+const ADDRESS: &'static str = "127.0.0.1:3012";
+
+const CFG: GameConfiguration = GameConfiguration {
+    area: Vector2 {
+        x: 600000,
+        y: 400000,
+    },
+    paddle: Vector2 {
+        x: 10000,
+        y: 50000,
+    },
+    ball_size: 10000,
+    goal_opening: None,
+    walls: REFLECTING_WALLS,
+    mode: Mode::Classic,
+    multiball: None,
+    power_ups: None,
+    field: None,
+    paddle_physics: None,
+    paddle_zone: None,
+    abilities: Vec::new(),
+    handicaps: Vec::new(),
+    difficulty: None,
+    tennis: None,
+    obstacles: Vec::new(),
+    bricks: Vec::new()
+};
+
+fn main() {
+    env_logger::init().unwrap();
+    println!("Listening for WebSocket clients at ws://{}", ADDRESS);
+    if let Err(e) = websocket::listen(ADDRESS, CFG.clone()) {
+        println!("Could not run server: {}", e);
+    }
+}
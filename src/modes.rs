@@ -0,0 +1,223 @@
+use std::hash::Hasher;
+use cgmath::{ Vector2 };
+use desync::StateHasher;
+use super::*;
+
+// The built-in modes. Each implements the hooks where its rules differ from
+// classic pong, which are the defaults of GameMode.
+
+pub fn from_configuration(mode: Mode) -> Box<dyn GameMode> {
+    match mode {
+        Mode::Classic => { Box::new(Classic) }
+        Mode::FourPlayer { lives } => { Box::new(FourPlayer { lives: lives }) }
+        Mode::Doubles { forward_offset } => { Box::new(Doubles { forward_offset: forward_offset }) }
+        Mode::Squash => { Box::new(Squash) }
+        Mode::Practice { pattern, speed } => { Box::new(Practice { pattern: pattern, speed: speed, serves: 0 }) }
+        Mode::Breakout { lives } => { Box::new(Breakout { lives: lives }) }
+        Mode::Timed { duration } => { Box::new(Timed { duration: duration, elapsed: 0, last_t: 0 }) }
+    }
+}
+
+#[derive(Debug)]
+pub struct Classic;
+
+impl GameMode for Classic {}
+
+#[derive(Debug)]
+pub struct FourPlayer {
+    pub lives: u8
+}
+
+impl GameMode for FourPlayer {
+    fn players(&self) -> Vec<Player> {
+        vec![Player::Left, Player::Right, Player::Top, Player::Bottom]
+    }
+
+    fn starting_lives(&self) -> u8 {
+        self.lives
+    }
+
+    // The last player standing keeps their paddle
+    fn on_goal(&mut self, game: &mut Game, _ball: ID, player: Player, callback: &mut dyn FnMut(Event)) -> bool {
+        let others = game.players().into_iter().any(|other| other != player && game.lives(other) > 0);
+        if game.lose_life(player, callback) == 0 && others {
+            game.eliminate(player, callback);
+        }
+        true
+    }
+
+    // Over once at most one player has lives left
+    fn is_finished(&self, game: &Game) -> bool {
+        game.players().into_iter().filter(|&player| game.lives(player) > 0).count() <= 1
+    }
+}
+
+#[derive(Debug)]
+pub struct Doubles {
+    pub forward_offset: i64
+}
+
+impl GameMode for Doubles {
+    fn players(&self) -> Vec<Player> {
+        vec![Player::Left, Player::Right, Player::LeftForward, Player::RightForward]
+    }
+
+    fn paddle_offset(&self, player: Player) -> i64 {
+        match player {
+            Player::LeftForward | Player::RightForward => { self.forward_offset }
+            _ => { 0 }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Squash;
+
+impl GameMode for Squash {
+    fn players(&self) -> Vec<Player> {
+        vec![Player::Left]
+    }
+
+    fn on_goal(&mut self, game: &mut Game, _ball: ID, player: Player, callback: &mut dyn FnMut(Event)) -> bool {
+        let streak = game.score(player);
+        game.set_score(player, 0);
+        callback(Event::StreakEnded{player: player, streak: streak});
+        true
+    }
+
+    fn on_hit(&mut self, game: &mut Game, _ball: ID, player: Player, callback: &mut dyn FnMut(Event)) {
+        game.score_point(player, callback);
+    }
+}
+
+#[derive(Debug)]
+pub struct Practice {
+    pub pattern: ServePattern,
+    pub speed: i64,
+    serves: u64
+}
+
+impl Practice {
+    // The launcher sits in front of the right wall
+    fn serve(&mut self, game: &mut Game, id: ID, callback: &mut dyn FnMut(Event)) {
+        let speed = self.speed;
+        let vertical = match self.pattern {
+            ServePattern::Straight => { 0 }
+            ServePattern::Alternating => { if self.serves % 2 == 0 { speed / 3 } else { -speed / 3 } }
+            ServePattern::Random => { game.rng().range(-speed / 2, speed / 2 + 1) }
+        };
+        self.serves += 1;
+        let x = game.configuration().area.x - game.entity(id).unwrap().size.x;
+        game.serve(id, Vector2::new(x, 0), Vector2::new(-speed, vertical), callback);
+    }
+}
+
+fn balls(game: &Game) -> Vec<EntityView> {
+    game.entities().into_iter().filter(|e| e.entity == Entity::Ball).collect()
+}
+
+impl GameMode for Practice {
+    fn players(&self) -> Vec<Player> {
+        vec![Player::Left]
+    }
+
+    fn on_start(&mut self, game: &mut Game, callback: &mut dyn FnMut(Event)) {
+        for ball in balls(game) {
+            self.serve(game, ball.id, callback);
+        }
+    }
+
+    fn on_reset(&mut self, _game: &mut Game) {
+        self.serves = 0;
+    }
+
+    // Returns that make it back to the launcher score and bring the next serve
+    fn on_tick(&mut self, game: &mut Game, callback: &mut dyn FnMut(Event)) {
+        for ball in balls(game) {
+            if ball.position.x > game.configuration().area.x {
+                game.score_point(Player::Left, callback);
+                self.serve(game, ball.id, callback);
+            }
+        }
+    }
+
+    // Misses just bring the next serve
+    fn on_goal(&mut self, game: &mut Game, ball: ID, _player: Player, callback: &mut dyn FnMut(Event)) -> bool {
+        self.serve(game, ball, callback);
+        false
+    }
+
+    fn write_state(&self, hasher: &mut StateHasher) {
+        hasher.write_u64(self.serves);
+    }
+}
+
+#[derive(Debug)]
+pub struct Breakout {
+    pub lives: u8
+}
+
+impl GameMode for Breakout {
+    fn players(&self) -> Vec<Player> {
+        vec![Player::Bottom]
+    }
+
+    fn starting_lives(&self) -> u8 {
+        self.lives
+    }
+
+    // The paddle stays around for when the game is reset
+    fn on_goal(&mut self, game: &mut Game, _ball: ID, player: Player, callback: &mut dyn FnMut(Event)) -> bool {
+        game.lose_life(player, callback);
+        true
+    }
+
+    // Over when the player runs out of lives or bricks
+    fn is_finished(&self, game: &Game) -> bool {
+        game.players().into_iter().all(|player| game.lives(player) == 0) || game.bricks_left() == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct Timed {
+    pub duration: u64,
+    // Play time so far, which stops between rounds
+    elapsed: u64,
+    last_t: u64
+}
+
+impl Timed {
+    fn remaining(&self) -> u64 {
+        self.duration.saturating_sub(self.elapsed)
+    }
+}
+
+impl GameMode for Timed {
+    fn on_start(&mut self, game: &mut Game, callback: &mut dyn FnMut(Event)) {
+        self.last_t = game.time();
+        callback(Event::ClockTick { remaining: self.remaining() });
+    }
+
+    fn on_reset(&mut self, _game: &mut Game) {
+        self.elapsed = 0;
+    }
+
+    fn on_tick(&mut self, game: &mut Game, callback: &mut dyn FnMut(Event)) {
+        let before = self.remaining();
+        self.elapsed += game.time() - self.last_t;
+        self.last_t = game.time();
+        let remaining = self.remaining();
+        if before > 0 && (remaining + 999999) / 1000000 != (before + 999999) / 1000000 {
+            callback(Event::ClockTick { remaining: remaining });
+        }
+    }
+
+    // Tied at the end of time, the next point wins
+    fn is_finished(&self, game: &Game) -> bool {
+        self.remaining() == 0 && game.score(Player::Left) != game.score(Player::Right)
+    }
+
+    fn write_state(&self, hasher: &mut StateHasher) {
+        hasher.write_u64(self.elapsed);
+    }
+}
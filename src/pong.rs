@@ -1,8 +1,28 @@
 extern crate cgmath;
+extern crate ws;
+extern crate rustc_serialize;
+extern crate toml;
+#[macro_use]
+extern crate log;
 
+pub mod arena;
+pub mod desync;
+pub mod entities;
+pub mod host;
+pub mod modes;
+pub mod rng;
+pub mod websocket;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::Hasher;
 use std::ops::Sub;
 use cgmath::{ Vector2 };
+use desync::StateHasher;
+use entities::{ EntityData, EntityStore };
+use rng::Rng;
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum State {
     Uninitialized,
     Ready,
@@ -11,15 +31,43 @@ pub enum State {
 
 #[derive(Debug)]
 pub enum Error {
-    NotInitialized
+    NotInitialized,
+    UnknownPeer,
+    NotHost,
+    WrongPlayer,
+    RateLimited,
+    UnknownPlayer,
+    GameOver,
+    TimeOutOfRange,
+    InModeHook
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Player {
-    Left, Right
+    Left, Right, Top, Bottom,
+    // Second paddles of the left and right teams in doubles
+    LeftForward, RightForward
 }
 
+impl Player {
+    // Teammates share the score of their side
+    pub fn side(&self) -> Player {
+        match *self {
+            Player::LeftForward => { Player::Left }
+            Player::RightForward => { Player::Right }
+            player => { player }
+        }
+    }
+}
+
+// Horizontal paddles move Up towards positive x and Down towards negative x.
+// Forward and Back move paddles towards the center and back to their goal line
+// within the paddle zone.
+#[derive(Clone, Copy, Debug)]
 pub enum Direction {
-    Up, Neutral, Down
+    Up, Neutral, Down,
+    Forward, Back,
+    UpForward, UpBack, DownForward, DownBack
 }
 
 pub enum Action {
@@ -27,86 +75,729 @@ pub enum Action {
     Start,
     Reset { seed: i64 },
     Time { t: u64 },
-    Move { player: Player, direction: Direction }
+    // Take effect at the first tick starting at or after t. Move is MoveAxis
+    // with the stick all the way up, centered or all the way down, and MoveAxis
+    // stops any Forward or Back movement.
+    Move { t: u64, player: Player, direction: Direction },
+    // Analog input from -1 to 1, kept to thousandths
+    MoveAxis { t: u64, player: Player, value: f64 },
+    // Moves the paddle towards y at full speed until another input. Top and
+    // bottom paddles take it as x.
+    MoveTo { t: u64, player: Player, y: i64 },
+    // Uses the player's ability, unless it is still cooling down
+    Ability { t: u64, player: Player }
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Entity {
-    LeftPaddle, RightPaddle, Ball
+    LeftPaddle, RightPaddle, TopPaddle, BottomPaddle, Ball, PowerUp, Obstacle, Brick
+}
+
+// Grow, BallSpeed and SlowMotion help the collecting player, the others hinder
+// the opposing sides. InvisibleBall is left to frontends to hide the balls.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PowerUp {
+    Grow, Shrink, BallSpeed, SlowMotion, ReverseControls, InvisibleBall
 }
 
+// Dash speeds up the paddle, Catch holds the next ball it hits for a moment
+// and lets it go aimed the way the paddle is moving, and Smash sends the next
+// ball back faster
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Ability {
+    Dash, Catch, Smash
+}
+
+const POWER_UPS: [PowerUp; 6] = [PowerUp::Grow, PowerUp::Shrink, PowerUp::BallSpeed,
+                                 PowerUp::SlowMotion, PowerUp::ReverseControls, PowerUp::InvisibleBall];
+
 pub type ID = u64;
 
+// Paddle speed per tick at full input when there is no paddle physics
+const PADDLE_SPEED: i64 = 300;
+// Multiple of their speed that dashing paddles move
+const DASH_SPEED: i64 = 3;
+// Percentage of its speed that a smashed ball leaves with
+const SMASH_SPEED: i64 = 150;
+// Ticks that caught balls are held
+const CATCH_TICKS: u64 = 300;
+
 pub enum Event {
     Create { id: ID, entity: Entity, x: i64, y: i64 },
     Destroy { id: ID },
     Move { id: ID, x: i64, y: i64 },
-    Goal { player: Player, score: u8 },
+    // New half-extents of a paddle
+    Resize { id: ID, w: i64, h: i64 },
+    Goal { player: Player, score: ScoreReport },
+    LifeLost { player: Player, lives: u8 },
+    StreakEnded { player: Player, streak: u8 },
+    BrickDestroyed { id: ID },
+    PowerUpSpawned { id: ID, power_up: PowerUp },
+    PowerUpCollected { id: ID, player: Player, power_up: PowerUp },
+    PowerUpExpired { player: Player, power_up: PowerUp },
+    AbilityUsed { player: Player, ability: Ability },
+    // Microseconds of play left in a timed match, every whole second
+    ClockTick { remaining: u64 },
     Reset,
     RoundStart
 }
 
-#[derive(Clone)]
+// The scoring side's score after a point. Without tennis scoring there are only
+// points, with it the points are those in the current game and start over with
+// every game, as games do with every set.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ScoreReport {
+    pub points: u8,
+    pub games: u8,
+    pub sets: u8,
+    pub won: Won
+}
+
+// What winning the point won
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Won {
+    Point, Game, Set, Match
+}
+
+// Read-only snapshot of an entity. Sizes are half-extents like in GameConfiguration.
+#[derive(Clone, Copy, Debug)]
+pub struct EntityView {
+    pub id: ID,
+    pub entity: Entity,
+    pub position: Vector2<i64>,
+    pub velocity: Vector2<i64>,
+    pub size: Vector2<i64>
+}
+
+#[derive(Clone, Debug)]
 pub struct GameConfiguration {
     pub area: Vector2<i64>,
     pub paddle: Vector2<i64>,
-    pub ball_size: i64
+    pub ball_size: i64,
+    // Half the size of the goal opening in the middle of each defended wall,
+    // the rest of the wall reflects the ball. None makes the whole wall the goal.
+    pub goal_opening: Option<i64>,
+    pub walls: WallConfiguration,
+    pub mode: Mode,
+    pub multiball: Option<MultiballConfiguration>,
+    pub power_ups: Option<PowerUpConfiguration>,
+    pub field: Option<FieldConfiguration>,
+    pub paddle_physics: Option<PaddlePhysicsConfiguration>,
+    // How far paddles can move forward from their goal line. None keeps them on it.
+    pub paddle_zone: Option<i64>,
+    pub abilities: Vec<AbilityConfiguration>,
+    pub handicaps: Vec<HandicapConfiguration>,
+    pub difficulty: Option<DifficultyConfiguration>,
+    pub tennis: Option<TennisConfiguration>,
+    pub obstacles: Vec<ObstacleConfiguration>,
+    pub bricks: Vec<BrickConfiguration>
+}
+
+impl GameConfiguration {
+    // Half-extents of the player's paddle standing upright
+    pub fn paddle_size(&self, player: Player) -> Vector2<i64> {
+        self.handicap(player).and_then(|h| h.paddle).unwrap_or(self.paddle)
+    }
+
+    // Speed per tick of the player's paddle at full input
+    pub fn paddle_speed(&self, player: Player) -> i64 {
+        let default = self.paddle_physics.as_ref().map(|physics| physics.max_speed).unwrap_or(PADDLE_SPEED);
+        self.handicap(player).and_then(|h| h.paddle_speed).unwrap_or(default)
+    }
+
+    pub fn starting_score(&self, player: Player) -> u8 {
+        self.handicap(player).map(|h| h.starting_score).unwrap_or(0)
+    }
+
+    fn handicap(&self, player: Player) -> Option<&HandicapConfiguration> {
+        self.handicaps.iter().find(|h| h.player == player)
+    }
+}
+
+// Built-in modes, see modes.rs for their rules
+#[derive(Clone, Copy, Debug)]
+pub enum Mode {
+    // Left and right score by getting the ball past each other
+    Classic,
+    // Every player defends their own wall and drops out with the last life,
+    // after which their wall reflects the ball
+    FourPlayer { lives: u8 },
+    // Left and right teams each have a forward paddle in front of their goal
+    // paddle, `forward_offset` towards the center
+    Doubles { forward_offset: i64 },
+    // The left player plays against the solid right wall and scores a point for
+    // every return, until a miss ends the streak
+    Squash,
+    // A launcher at the right wall serves at the left player in the given
+    // pattern, and returns that make it back to the wall score
+    Practice { pattern: ServePattern, speed: i64 },
+    // The bottom player clears the bricks, and the game is over when they run
+    // out of lives or bricks
+    Breakout { lives: u8 },
+    // Classic pong for `duration` microseconds of play, after which the higher
+    // score wins or the next point does if the scores are tied
+    Timed { duration: u64 }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ServePattern {
+    Straight,
+    // High and low in turns
+    Alternating,
+    Random
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WallBehaviour {
+    Reflect,
+    // The ball comes back in from the opposite wall
+    Wrap,
+    // The player who last hit the ball concedes, as if they had let in a goal
+    Absorb
+}
+
+// How each wall treats the ball wherever it is not a goal
+#[derive(Clone, Copy, Debug)]
+pub struct WallConfiguration {
+    pub left: WallBehaviour,
+    pub right: WallBehaviour,
+    pub top: WallBehaviour,
+    pub bottom: WallBehaviour
+}
+
+pub const REFLECTING_WALLS: WallConfiguration = WallConfiguration {
+    left: WallBehaviour::Reflect,
+    right: WallBehaviour::Reflect,
+    top: WallBehaviour::Reflect,
+    bottom: WallBehaviour::Reflect
+};
+
+impl WallConfiguration {
+    pub fn behaviour(&self, player: Player) -> WallBehaviour {
+        match player.side() {
+            Player::Left => { self.left }
+            Player::Right => { self.right }
+            Player::Top => { self.top }
+            _ => { self.bottom }
+        }
+    }
+}
+
+// Extra balls spawn at the center every `interval` ticks of play and whenever
+// the total score reaches a multiple of `score_interval`. Zero disables either rule.
+#[derive(Clone, Debug)]
+pub struct MultiballConfiguration {
+    pub interval: u64,
+    pub score_interval: u8,
+    pub max_balls: usize
+}
+
+// A random power-up spawns every `interval` ticks of play in the middle half of
+// the field, up to `max_power_ups` at a time. Balls collect them for the player
+// who hit them last, and their effects last `duration` ticks.
+#[derive(Clone, Debug)]
+pub struct PowerUpConfiguration {
+    pub interval: u64,
+    pub duration: u64,
+    pub max_power_ups: usize,
+    pub size: i64
+}
+
+// Forces on the balls. Accelerations are given as the change in velocity over
+// 1000 ticks, so that small forces need no fractions. `curve` is the
+// acceleration in thousandths of the hitting paddle's velocity, which lasts
+// until the ball bounces off something again.
+#[derive(Clone, Debug)]
+pub struct FieldConfiguration {
+    pub gravity: Vector2<i64>,
+    pub wind: Option<WindConfiguration>,
+    pub curve: i64
+}
+
+// Gusts blow for the first `duration` ticks of every `period`, alternating in
+// direction so that the wind evens out over time
+#[derive(Clone, Debug)]
+pub struct WindConfiguration {
+    pub force: Vector2<i64>,
+    pub period: u64,
+    pub duration: u64
+}
+
+// Paddles accelerate by `acceleration` per tick towards `max_speed` times their
+// input, and slow down by `friction` per tick once the input is released.
+// Without it paddles move at a constant speed as long as the input is held.
+#[derive(Clone, Debug)]
+pub struct PaddlePhysicsConfiguration {
+    pub acceleration: i64,
+    pub max_speed: i64,
+    pub friction: i64
+}
+
+// Gives the player an ability, which can be used again `cooldown` ticks after
+// it was last used. Dash lasts `duration` ticks, and Catch and Smash are used up
+// by the first hit within `duration` ticks.
+#[derive(Clone, Debug)]
+pub struct AbilityConfiguration {
+    pub player: Player,
+    pub ability: Ability,
+    pub cooldown: u64,
+    pub duration: u64
+}
+
+// Settings for one player in place of the shared ones. `paddle` replaces
+// GameConfiguration::paddle and `paddle_speed` the speed at full input, with or
+// without paddle physics. Teammates share the score of their side, so only the
+// starting score of the player on the goal line counts.
+#[derive(Clone, Debug)]
+pub struct HandicapConfiguration {
+    pub player: Player,
+    pub paddle: Option<Vector2<i64>>,
+    pub paddle_speed: Option<i64>,
+    pub starting_score: u8
+}
+
+// Paddles shrink by `shrink` thousandths of their length for every point their
+// side has, and grow by `rubber_band` thousandths for every point their side is
+// behind the leader, staying between `min_scale` and `max_scale` thousandths
+// of their length. Zero disables either rule.
+#[derive(Clone, Debug)]
+pub struct DifficultyConfiguration {
+    pub shrink: i64,
+    pub rubber_band: i64,
+    pub min_scale: i64,
+    pub max_scale: i64
+}
+
+// Tennis scoring between each side and its opponent. A side wins a game with
+// four points and two ahead, a set with `games` games and two ahead, and the
+// match with `sets` sets, which ends the game. With `tiebreak_points` above
+// zero, sets tied at `games` games each go to a tiebreak game played to that
+// many points, two ahead.
+#[derive(Clone, Debug)]
+pub struct TennisConfiguration {
+    pub games: u8,
+    pub sets: u8,
+    pub tiebreak_points: u8
+}
+
+// Rectangles the ball bounces off. Moving obstacles bounce back and forth
+// within `travel` of their position, see the arena module for loading them.
+#[derive(Clone, Debug)]
+pub struct ObstacleConfiguration {
+    pub position: Vector2<i64>,
+    pub size: Vector2<i64>,
+    pub velocity: Vector2<i64>,
+    pub travel: Vector2<i64>
 }
 
+// Bricks are destroyed after the ball has hit them `hit_points` times, see the
+// arena module for loading them from level files
+#[derive(Clone, Debug)]
+pub struct BrickConfiguration {
+    pub position: Vector2<i64>,
+    pub size: Vector2<i64>,
+    pub hit_points: u8
+}
+
+#[derive(Debug)]
 struct PlayerData {
-    id: ID,
+    player: Player,
+    paddle: Option<ID>,
+    control: Control,
     score: u8,
-    position: Vector2<i64>,
-    velocity: Vector2<i64>
+    games: u8,
+    sets: u8,
+    // Points won over the whole game, which tennis scoring does not start over
+    goals: u32,
+    lives: u8,
+    // The tick the ability can be used again and the last tick it is active
+    ability_ready: u64,
+    ability_until: u64
+}
+#[derive(Debug)]
+struct Input {
+    t: u64,
+    player: Player,
+    control: Control
 }
-struct BallData {
-    id: ID,
-    position: Vector2<i64>,
-    velocity: Vector2<i64>
+// Input along the wall and forward in thousandths, or the position along the
+// wall the paddle is headed to. Ability inputs leave the control as it is.
+#[derive(Clone, Copy, Debug)]
+enum Control {
+    Axis(i64, i64),
+    Target(i64),
+    Ability
 }
+#[derive(Debug)]
+struct Effect {
+    power_up: PowerUp,
+    player: Player,
+    targets: Vec<Player>,
+    expires: u64
+}
+// A ball held by a paddle until the release tick, after which it leaves with
+// the velocity it bounced off with
+#[derive(Clone, Debug)]
+struct Catch {
+    paddle: ID,
+    offset: Vector2<i64>,
+    velocity: Vector2<i64>,
+    release: u64
+}
+#[derive(Debug)]
 pub struct Game {
     state: State,
     cfg: GameConfiguration,
     t: u64,
-    left: PlayerData,
-    right: PlayerData,
-    ball: BallData
+    tick: u64,
+    inputs: Vec<Input>,
+    entities: EntityStore,
+    players: Vec<PlayerData>,
+    rng: Rng,
+    power_ups: BTreeMap<ID, PowerUp>,
+    // Where each obstacle starts and how far it may move from there
+    obstacles: BTreeMap<ID, ObstacleConfiguration>,
+    // Hit points left on each brick
+    bricks: BTreeMap<ID, u8>,
+    // The player whose paddle last hit each ball
+    owners: BTreeMap<ID, Player>,
+    // Curve put on each ball by the paddle that last hit it
+    spins: BTreeMap<ID, Vector2<i64>>,
+    catches: BTreeMap<ID, Catch>,
+    effects: Vec<Effect>,
+    // Only taken out while one of its hooks runs
+    mode: Option<Box<dyn GameMode>>
 }
 
 impl Game {
     pub fn new(cfg: GameConfiguration) -> Game {
+        let mode = modes::from_configuration(cfg.mode);
+        Game::with_mode(cfg, mode)
+    }
+
+    // Plays by the rules of the given mode instead of cfg.mode
+    pub fn with_mode(cfg: GameConfiguration, mode: Box<dyn GameMode>) -> Game {
+        let mut entities = EntityStore::new();
+        let players = mode.players().into_iter().map(|player| PlayerData {
+            player: player,
+            paddle: Some(entities.insert(paddle_data(&cfg, player, mode.paddle_offset(player)))),
+            control: Control::Axis(0, 0),
+            score: cfg.starting_score(player),
+            games: 0,
+            sets: 0,
+            goals: 0,
+            lives: mode.starting_lives(),
+            ability_ready: 0,
+            ability_until: 0
+        }).collect();
+        entities.insert(EntityData {
+            kind: Entity::Ball,
+            size: Vector2::new(cfg.ball_size, cfg.ball_size),
+            position: Vector2::new(0, 0),
+            velocity: Vector2::new(240, 240)
+        });
+        let obstacles = cfg.obstacles.iter()
+            .map(|obstacle| (entities.insert(obstacle_data(obstacle)), obstacle.clone()))
+            .collect();
+        let bricks = cfg.bricks.iter()
+            .map(|brick| (entities.insert(brick_data(brick)), brick.hit_points))
+            .collect();
+
         let game = Game {
             state: State::Uninitialized,
             t: 0,
-            left: PlayerData {
-                id: 0,
-                score: 0,
-                position: Vector2::new(cfg.paddle.x - cfg.area.x, 0),
-                velocity: Vector2::new(0, 0)
-            },
-            right: PlayerData {
-                id: 1,
-                score: 0,
-                position: Vector2::new(cfg.area.x - cfg.paddle.x, 0),
-                velocity: Vector2::new(0, 0)
-            },
-            ball: BallData {
-                id: 2,
-                position: Vector2::new(0, 0),
-                velocity: Vector2::new(240, 240)
-            },
+            tick: 0,
+            inputs: Vec::new(),
+            entities: entities,
+            players: players,
+            rng: Rng::new(0),
+            power_ups: BTreeMap::new(),
+            obstacles: obstacles,
+            bricks: bricks,
+            owners: BTreeMap::new(),
+            spins: BTreeMap::new(),
+            catches: BTreeMap::new(),
+            effects: Vec::new(),
+            mode: Some(mode),
             cfg: cfg
         };
         return game;
     }
 
+    // Mode hooks cannot drive the game they are called from
     pub fn process<F>(&mut self, action: Action, callback: F) -> Result<(), Error> where F: FnMut(Event) {
+        if self.mode.is_none() {
+            return Err(Error::InModeHook);
+        }
         match action {
             Action::Initialize => { action_initialize(self, callback) }
             Action::Start => { action_start(self, callback) }
             Action::Reset{ seed } => { action_reset(self, seed, callback) }
             Action::Time{ t } => { action_time(self, t, callback) }
-            Action::Move{ player, direction } => { action_move(self, player, direction, callback) }
+            Action::Move{ t, player, direction } => {
+                let (along, forward) = direction_axes(direction);
+                action_move(self, t, player, Control::Axis(along, forward), callback)
+            }
+            Action::MoveAxis{ t, player, value } => { action_move(self, t, player, Control::Axis(value_axis(value), 0), callback) }
+            Action::MoveTo{ t, player, y } => { action_move(self, t, player, Control::Target(y), callback) }
+            Action::Ability{ t, player } => { action_move(self, t, player, Control::Ability, callback) }
+        }
+    }
+
+    // Entities spawned before Initialize are announced by it instead. Obstacles
+    // spawned this way stay where they are put, see spawn_obstacle.
+    pub fn spawn<F>(&mut self, data: EntityData, callback: &mut F) -> ID where F: FnMut(Event) + ?Sized {
+        let (entity, x, y) = (data.kind, data.position.x, data.position.y);
+        let obstacle = ObstacleConfiguration {
+            position: data.position,
+            size: data.size,
+            velocity: data.velocity,
+            travel: Vector2::new(0, 0)
+        };
+        let id = self.entities.insert(data);
+        if entity == Entity::Obstacle {
+            self.obstacles.insert(id, obstacle);
         }
+        if self.state != State::Uninitialized {
+            callback(Event::Create { id: id, entity: entity, x: x, y: y });
+        }
+        id
+    }
+
+    // Moves back and forth within its travel, and goes back to where it
+    // started on Reset
+    pub fn spawn_obstacle<F>(&mut self, obstacle: ObstacleConfiguration, callback: &mut F) -> ID where F: FnMut(Event) + ?Sized {
+        let id = self.spawn(obstacle_data(&obstacle), callback);
+        self.obstacles.insert(id, obstacle);
+        id
+    }
+
+    pub fn destroy<F>(&mut self, id: ID, callback: &mut F) where F: FnMut(Event) + ?Sized {
+        self.power_ups.remove(&id);
+        self.obstacles.remove(&id);
+        self.bricks.remove(&id);
+        self.owners.remove(&id);
+        self.spins.remove(&id);
+        self.catches.remove(&id);
+        for player_data in self.players.iter_mut().filter(|p| p.paddle == Some(id)) {
+            player_data.paddle = None;
+        }
+        if self.entities.remove(id).is_some() && self.state != State::Uninitialized {
+            callback(Event::Destroy { id: id });
+        }
+    }
+
+    // Scores a point for the player's side
+    pub fn score_point<F>(&mut self, player: Player, callback: &mut F) where F: FnMut(Event) + ?Sized {
+        let side = player.side();
+        let i = match self.players.iter().position(|p| p.player == side) {
+            Some(i) => { i }
+            None => { return; }
+        };
+        self.players[i].score = self.players[i].score.saturating_add(1);
+        self.players[i].goals += 1;
+        let won = match (self.cfg.tennis.clone(), self.players.iter().position(|p| p.player == opponent(side))) {
+            (Some(tennis), Some(j)) => { win_tennis_point(&mut self.players, i, j, &tennis) }
+            _ => { Won::Point }
+        };
+        let p = &self.players[i];
+        callback(Event::Goal{player: side, score: ScoreReport {
+            points: p.score,
+            games: p.games,
+            sets: p.sets,
+            won: won
+        }});
+    }
+
+    // Returns the lives the player has left
+    pub fn lose_life<F>(&mut self, player: Player, callback: &mut F) -> u8 where F: FnMut(Event) + ?Sized {
+        let lives = match self.get_player(&player) {
+            Some(player_data) => {
+                player_data.lives = player_data.lives.saturating_sub(1);
+                player_data.lives
+            }
+            None => { return 0; }
+        };
+        callback(Event::LifeLost{player: player, lives: lives});
+        lives
+    }
+
+    // Removes the player's paddle, after which their wall reflects the ball
+    pub fn eliminate<F>(&mut self, player: Player, callback: &mut F) where F: FnMut(Event) + ?Sized {
+        let paddle = self.get_player(&player).and_then(|p| p.paddle.take());
+        if let Some(paddle) = paddle {
+            self.destroy(paddle, callback);
+        }
+    }
+
+    // Puts the ball back in play from the position, as if no paddle had hit it
+    pub fn serve<F>(&mut self, id: ID, position: Vector2<i64>, velocity: Vector2<i64>, callback: &mut F)
+        where F: FnMut(Event) + ?Sized {
+        self.owners.remove(&id);
+        self.spins.remove(&id);
+        self.catches.remove(&id);
+        if let Some(ball) = self.entities.get_mut(id) {
+            ball.position = position;
+            ball.velocity = velocity;
+            if self.state != State::Uninitialized {
+                callback(Event::Move { id: id, x: position.x, y: position.y });
+            }
+        }
+    }
+
+    pub fn set_score(&mut self, player: Player, score: u8) {
+        if let Some(player_data) = self.players.iter_mut().find(|p| p.player == player.side()) {
+            player_data.score = score;
+        }
+    }
+
+    // Seeded by Reset, so modes drawing from it stay in step between peers
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn time(&self) -> u64 {
+        self.t
+    }
+
+    pub fn configuration(&self) -> &GameConfiguration {
+        &self.cfg
+    }
+
+    pub fn players(&self) -> Vec<Player> {
+        self.players.iter().map(|p| p.player).collect()
+    }
+
+    pub fn score(&self, player: Player) -> u8 {
+        self.players.iter().find(|p| p.player == player.side()).map(|p| p.score).unwrap_or(0)
+    }
+
+    pub fn games(&self, player: Player) -> u8 {
+        self.players.iter().find(|p| p.player == player.side()).map(|p| p.games).unwrap_or(0)
+    }
+
+    pub fn sets(&self, player: Player) -> u8 {
+        self.players.iter().find(|p| p.player == player.side()).map(|p| p.sets).unwrap_or(0)
+    }
+
+    // The side's points as tennis calls them, 0, 15, 30, 40 and AD for
+    // advantage, or plain numbers in tiebreaks and without tennis scoring
+    pub fn call(&self, player: Player) -> String {
+        let side = self.players.iter().find(|p| p.player == player.side());
+        let other = self.players.iter().find(|p| p.player == opponent(player.side()));
+        match (self.cfg.tennis.as_ref(), side, other) {
+            (Some(tennis), Some(side), Some(other)) if !is_tiebreak(side, other, tennis) => {
+                match side.score {
+                    0 => { "0" }
+                    1 => { "15" }
+                    2 => { "30" }
+                    points if points > other.score && other.score >= 3 => { "AD" }
+                    _ => { "40" }
+                }.to_string()
+            }
+            _ => { self.score(player).to_string() }
+        }
+    }
+
+    pub fn lives(&self, player: Player) -> u8 {
+        self.players.iter().find(|p| p.player == player).map(|p| p.lives).unwrap_or(0)
+    }
+
+    pub fn entities(&self) -> Vec<EntityView> {
+        self.entities.iter().map(|(&id, e)| entity_view(id, e)).collect()
+    }
+
+    pub fn entity(&self, id: ID) -> Option<EntityView> {
+        self.entities.get(id).map(|e| entity_view(id, e))
+    }
+
+    pub fn bricks_left(&self) -> usize {
+        self.bricks.len()
+    }
+
+    // Stable across runs and platforms, so it can be compared between peers.
+    // Covers only the simulation so far, not the time or inputs still to come.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_u8(match self.state {
+            State::Uninitialized => { 0 }
+            State::Ready => { 1 }
+            State::Running => { 2 }
+        });
+        hasher.write_u64(self.tick);
+        for (&id, e) in self.entities.iter() {
+            hasher.write_u64(id);
+            hasher.write_u8(e.kind as u8);
+            hasher.write_vector(e.size);
+            hasher.write_vector(e.position);
+            hasher.write_vector(e.velocity);
+        }
+        for p in self.players.iter() {
+            hasher.write_u8(p.player as u8);
+            hasher.write_u64(p.paddle.unwrap_or(ID::max_value()));
+            write_control(&mut hasher, p.control);
+            hasher.write_u8(p.score);
+            hasher.write_u8(p.games);
+            hasher.write_u8(p.sets);
+            hasher.write_u64(p.goals as u64);
+            hasher.write_u8(p.lives);
+            hasher.write_u64(p.ability_ready);
+            hasher.write_u64(p.ability_until);
+        }
+        hasher.write_u64(self.rng.state());
+        for (&id, &power_up) in self.power_ups.iter() {
+            hasher.write_u64(id);
+            hasher.write_u8(power_up as u8);
+        }
+        for (&id, obstacle) in self.obstacles.iter() {
+            hasher.write_u64(id);
+            hasher.write_vector(obstacle.position);
+            hasher.write_vector(obstacle.velocity);
+            hasher.write_vector(obstacle.travel);
+        }
+        for (&id, &hit_points) in self.bricks.iter() {
+            hasher.write_u64(id);
+            hasher.write_u8(hit_points);
+        }
+        for (&id, &player) in self.owners.iter() {
+            hasher.write_u64(id);
+            hasher.write_u8(player as u8);
+        }
+        for (&id, &spin) in self.spins.iter() {
+            hasher.write_u64(id);
+            hasher.write_vector(spin);
+        }
+        for (&id, catch) in self.catches.iter() {
+            hasher.write_u64(id);
+            hasher.write_u64(catch.paddle);
+            hasher.write_vector(catch.offset);
+            hasher.write_vector(catch.velocity);
+            hasher.write_u64(catch.release);
+        }
+        for effect in self.effects.iter() {
+            hasher.write_u8(effect.power_up as u8);
+            hasher.write_u8(effect.player as u8);
+            for &target in effect.targets.iter() {
+                hasher.write_u8(target as u8);
+            }
+            hasher.write_u64(effect.expires);
+        }
+        if let Some(ref mode) = self.mode {
+            mode.write_state(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    pub fn dump_state(&self) -> String {
+        format!("{:#?}", self)
     }
 
     fn require_initialized(&self) -> Result<(), Error> {
@@ -116,24 +807,208 @@ impl Game {
         }
     }
 
-    fn get_player(&mut self, player: &Player) -> &mut PlayerData {
-        match player {
-            &Player::Left => { &mut self.left }
-            &Player::Right => { &mut self.right }
+    fn get_player(&mut self, player: &Player) -> Option<&mut PlayerData> {
+        self.players.iter_mut().find(|p| p.player == *player)
+    }
+
+    // Players defend their wall as long as they have a paddle on it
+    fn defends(&self, player: Player) -> bool {
+        self.players.iter().any(|p| p.player == player && p.paddle.is_some())
+    }
+
+    fn is_over(&self) -> bool {
+        let match_won = self.cfg.tennis.as_ref()
+            .map(|tennis| self.players.iter().any(|p| p.sets >= tennis.sets))
+            .unwrap_or(false);
+        match_won || self.mode.as_ref().map(|mode| mode.is_finished(self)).unwrap_or(false)
+    }
+
+    fn goals(&self) -> u32 {
+        let lives = self.mode.as_ref().map(|mode| mode.starting_lives()).unwrap_or(0);
+        self.players.iter().map(|p| p.goals + lives.saturating_sub(p.lives) as u32).sum()
+    }
+}
+
+// The rules of a game. Game calls the hooks as the game is played, and their
+// defaults are the rules of classic pong so modes only override what they
+// change. Hooks are handed the game without its mode.
+pub trait GameMode: fmt::Debug {
+    // Players taking part, in the order peers are given them
+    fn players(&self) -> Vec<Player> {
+        vec![Player::Left, Player::Right]
+    }
+
+    // How far towards the center from their wall the player's paddle starts
+    fn paddle_offset(&self, _player: Player) -> i64 {
+        0
+    }
+
+    fn starting_lives(&self) -> u8 {
+        0
+    }
+
+    // A round starts
+    fn on_start(&mut self, _game: &mut Game, _callback: &mut dyn FnMut(Event)) {}
+
+    fn on_reset(&mut self, _game: &mut Game) {}
+
+    // Every tick, after everything but the balls has moved
+    fn on_tick(&mut self, _game: &mut Game, _callback: &mut dyn FnMut(Event)) {}
+
+    // The ball got past the player. Returns whether that ends the rally.
+    fn on_goal(&mut self, game: &mut Game, _ball: ID, player: Player, callback: &mut dyn FnMut(Event)) -> bool {
+        game.score_point(opponent(player), callback);
+        true
+    }
+
+    // The player's paddle hit the ball
+    fn on_hit(&mut self, _game: &mut Game, _ball: ID, _player: Player, _callback: &mut dyn FnMut(Event)) {}
+
+    // Once finished, Start fails with GameOver until the game is reset
+    fn is_finished(&self, _game: &Game) -> bool {
+        false
+    }
+
+    // Feeds whatever state the mode keeps to the state hash
+    fn write_state(&self, _hasher: &mut StateHasher) {}
+}
+
+fn with_mode<R, H>(game: &mut Game, hook: H) -> R where H: FnOnce(&mut dyn GameMode, &mut Game) -> R {
+    let mut mode = game.mode.take().unwrap();
+    let result = hook(&mut *mode, game);
+    game.mode = Some(mode);
+    result
+}
+
+// Top and bottom paddles are the side paddles turned sideways
+fn paddle_data(cfg: &GameConfiguration, player: Player, forward_offset: i64) -> EntityData {
+    let vertical = cfg.paddle_size(player);
+    let horizontal = Vector2::new(vertical.y, vertical.x);
+    let (kind, size, position) = match player {
+        Player::Left => { (Entity::LeftPaddle, vertical, Vector2::new(vertical.x - cfg.area.x, 0)) }
+        Player::Right => { (Entity::RightPaddle, vertical, Vector2::new(cfg.area.x - vertical.x, 0)) }
+        Player::Top => { (Entity::TopPaddle, horizontal, Vector2::new(0, cfg.area.y - vertical.x)) }
+        Player::Bottom => { (Entity::BottomPaddle, horizontal, Vector2::new(0, vertical.x - cfg.area.y)) }
+        Player::LeftForward => {
+            (Entity::LeftPaddle, vertical, Vector2::new(vertical.x - cfg.area.x + forward_offset, 0))
+        }
+        Player::RightForward => {
+            (Entity::RightPaddle, vertical, Vector2::new(cfg.area.x - vertical.x - forward_offset, 0))
         }
+    };
+    EntityData {
+        kind: kind,
+        size: size,
+        position: position,
+        velocity: Vector2::new(0, 0)
+    }
+}
+
+fn obstacle_data(obstacle: &ObstacleConfiguration) -> EntityData {
+    EntityData {
+        kind: Entity::Obstacle,
+        size: obstacle.size,
+        position: obstacle.position,
+        velocity: obstacle.velocity
+    }
+}
+
+fn brick_data(brick: &BrickConfiguration) -> EntityData {
+    EntityData {
+        kind: Entity::Brick,
+        size: brick.size,
+        position: brick.position,
+        velocity: Vector2::new(0, 0)
+    }
+}
+
+// Moves the tennis score on after players[i] won a point against players[j]
+fn win_tennis_point(players: &mut [PlayerData], i: usize, j: usize, tennis: &TennisConfiguration) -> Won {
+    let tiebreak = is_tiebreak(&players[i], &players[j], tennis);
+    let points = if tiebreak { tennis.tiebreak_points } else { 4 };
+    // Back at deuce the score goes back to 40 all, so that long deuces cannot
+    // run it up. Tiebreaks keep counting.
+    if !tiebreak && players[i].score == players[j].score && players[i].score >= 3 {
+        players[i].score = 3;
+        players[j].score = 3;
+        return Won::Point;
+    }
+    if players[i].score < points || (players[i].score as u16) < players[j].score as u16 + 2 {
+        return Won::Point;
+    }
+    players[i].score = 0;
+    players[j].score = 0;
+    players[i].games = players[i].games.saturating_add(1);
+    if !tiebreak && (players[i].games < tennis.games || (players[i].games as u16) < players[j].games as u16 + 2) {
+        return Won::Game;
+    }
+    players[i].games = 0;
+    players[j].games = 0;
+    players[i].sets += 1;
+    if players[i].sets < tennis.sets { Won::Set } else { Won::Match }
+}
+
+fn is_tiebreak(a: &PlayerData, b: &PlayerData, tennis: &TennisConfiguration) -> bool {
+    tennis.tiebreak_points > 0 && a.games == tennis.games && b.games == tennis.games
+}
+
+fn opponent(player: Player) -> Player {
+    match player {
+        Player::Left | Player::LeftForward => { Player::Right }
+        Player::Right | Player::RightForward => { Player::Left }
+        Player::Top => { Player::Bottom }
+        Player::Bottom => { Player::Top }
+    }
+}
+
+fn entity_view(id: ID, e: &EntityData) -> EntityView {
+    EntityView {
+        id: id,
+        entity: e.kind,
+        position: e.position,
+        velocity: e.velocity,
+        size: e.size
+    }
+}
+
+fn is_vertical_wall(player: Player) -> bool {
+    match player {
+        Player::Top | Player::Bottom => { false }
+        _ => { true }
+    }
+}
+
+fn is_vertical_paddle(kind: Entity) -> bool {
+    match kind {
+        Entity::LeftPaddle | Entity::RightPaddle => { true }
+        _ => { false }
+    }
+}
+
+fn is_horizontal_paddle(kind: Entity) -> bool {
+    match kind {
+        Entity::TopPaddle | Entity::BottomPaddle => { true }
+        _ => { false }
     }
 }
 
 fn action_initialize<F>(game: &mut Game, mut callback: F) -> Result<(), Error> where F: FnMut(Event) {
-    callback(Event::Create { id: game.left.id, entity: Entity::LeftPaddle, x: game.left.position.x, y: game.left.position.y });
-    callback(Event::Create { id: game.right.id, entity: Entity::RightPaddle, x: game.right.position.x, y: game.right.position.y });
-    callback(Event::Create { id: game.ball.id, entity: Entity::Ball, x: game.ball.position.x, y: game.ball.position.y });
+    for (&id, e) in game.entities.iter() {
+        callback(Event::Create { id: id, entity: e.kind, x: e.position.x, y: e.position.y });
+    }
     game.state = State::Ready;
+    update_difficulty(game, &mut callback);
     Ok(())
 }
 
 fn action_start<F>(game: &mut Game, mut callback: F) -> Result<(), Error> where F: FnMut(Event) {
     try!(game.require_initialized());
+    if game.is_over() {
+        return Err(Error::GameOver);
+    }
+    if game.state != State::Running {
+        with_mode(game, |mode, game| mode.on_start(game, &mut callback));
+    }
     game.state = State::Running;
     callback(Event::RoundStart);
     Ok(())
@@ -141,15 +1016,66 @@ fn action_start<F>(game: &mut Game, mut callback: F) -> Result<(), Error> where
 
 fn action_reset<F>(game: &mut Game, seed: i64, mut callback: F) -> Result<(), Error> where F: FnMut(Event) {
     try!(game.require_initialized());
-    game.left.position.y = 0;
-    game.right.position.y = 0;
-    game.ball.position.x = 0;
-    game.ball.position.y = 0;
-    game.ball.velocity.x = ((seed % 2) - 1) * 300;
+    game.rng = Rng::new(seed as u64);
+    end_effects(game, u64::max_value(), &mut callback);
+    for id in game.power_ups.keys().cloned().collect::<Vec<ID>>() {
+        game.destroy(id, &mut callback);
+    }
+    // Back to a single ball, like at the end of a rally
+    for id in game.entities.ids(Entity::Ball).into_iter().skip(1) {
+        game.destroy(id, &mut callback);
+    }
+    game.owners.clear();
+    game.spins.clear();
+    game.catches.clear();
+    for id in game.bricks.keys().cloned().collect::<Vec<ID>>() {
+        game.destroy(id, &mut callback);
+    }
+    for brick in game.cfg.bricks.clone() {
+        let id = game.spawn(brick_data(&brick), &mut callback);
+        game.bricks.insert(id, brick.hit_points);
+    }
+    let lives = game.mode.as_ref().unwrap().starting_lives();
+    for i in 0..game.players.len() {
+        game.players[i].score = game.cfg.starting_score(game.players[i].player);
+        game.players[i].games = 0;
+        game.players[i].sets = 0;
+        game.players[i].goals = 0;
+        game.players[i].lives = lives;
+        game.players[i].ability_ready = 0;
+        game.players[i].ability_until = 0;
+        if game.players[i].paddle.is_none() {
+            let player = game.players[i].player;
+            let paddle = paddle_data(&game.cfg, player, game.mode.as_ref().unwrap().paddle_offset(player));
+            game.players[i].paddle = Some(game.spawn(paddle, &mut callback));
+        }
+    }
+    with_mode(game, |mode, game| mode.on_reset(game));
+    update_difficulty(game, &mut callback);
+    let mode = game.mode.as_ref().unwrap();
+    let entities = &mut game.entities;
+    for player_data in game.players.iter() {
+        let line = paddle_data(&game.cfg, player_data.player, mode.paddle_offset(player_data.player)).position;
+        if let Some(p) = player_data.paddle.and_then(|paddle| entities.get_mut(paddle)) {
+            p.position = line;
+        }
+    }
+    for (_, e) in game.entities.iter_mut() {
+        if e.kind == Entity::Ball {
+            e.position = Vector2::new(0, 0);
+            e.velocity.x = ((seed % 2) - 1) * 300;
+        }
+    }
+    for (id, obstacle) in game.obstacles.iter() {
+        if let Some(e) = game.entities.get_mut(*id) {
+            e.position = obstacle.position;
+            e.velocity = obstacle.velocity;
+        }
+    }
     game.state = State::Ready;
-    callback(Event::Move{ id: game.left.id, x: game.left.position.x, y: 0 });
-    callback(Event::Move{ id: game.right.id, x: game.right.position.x, y: 0 });
-    callback(Event::Move{ id: game.ball.id, x: 0, y: 0 });
+    for (&id, e) in game.entities.iter() {
+        callback(Event::Move{ id: id, x: e.position.x, y: e.position.y });
+    }
     callback(Event::Reset);
     Ok(())
 }
@@ -160,15 +1086,18 @@ fn action_time<F>(game: &mut Game, t: u64, mut callback: F) -> Result<(), Error>
         State::Uninitialized => { Err(Error::NotInitialized) }
         State::Ready => {
             game.t = t;
+            apply_inputs(game, t, &mut callback);
             Ok(())
         }
         State::Running => {
+            // game.t stays on the last tick so that ticks do not depend on how time is split into actions
             let frame_time = 1000;
-            while t - game.t >= frame_time {
+            while game.state == State::Running && t.saturating_sub(game.t) >= frame_time {
+                let frame_start = game.t;
+                apply_inputs(game, frame_start, &mut callback);
                 game.t += frame_time;
                 try!(advance_frame(game, &mut callback));
             }
-            game.t = t;
 
             Ok(())
         }
@@ -176,80 +1105,747 @@ fn action_time<F>(game: &mut Game, t: u64, mut callback: F) -> Result<(), Error>
 }
 
 fn advance_frame<F>(game: &mut Game, callback: &mut F) -> Result<(), Error> where F: FnMut(Event) {
+    game.tick += 1;
+    let tick = game.tick;
+    let positions: BTreeMap<ID, Vector2<i64>> = game.entities.iter().map(|(&id, e)| (id, e.position)).collect();
+    end_effects(game, tick, callback);
+    apply_forces(game);
+    steer_paddles(game);
+
     {
-        let min_paddle_y = game.cfg.paddle.y - game.cfg.area.y;
-        let max_paddle_y = game.cfg.area.y - game.cfg.paddle.y;
-        let mut players = [&mut game.left, &mut game.right];
-        for p in players.iter_mut() {
-            p.position += p.velocity;
-            p.position.y = clamp(p.position.y, min_paddle_y, max_paddle_y);
+        let area = game.cfg.area;
+        let ball_speed = ball_speed(game);
+        let dashing: Vec<ID> = game.players.iter()
+            .filter(|p| active_ability(game, p) == Some(Ability::Dash))
+            .filter_map(|p| p.paddle)
+            .collect();
+        for (id, e) in game.entities.iter_mut() {
+            if e.kind == Entity::Ball {
+                e.position += e.velocity * ball_speed / 100;
+            } else if dashing.contains(id) {
+                e.position += e.velocity * DASH_SPEED;
+            } else {
+                e.position += e.velocity;
+            }
+            if is_vertical_paddle(e.kind) {
+                e.position.y = clamp(e.position.y, e.size.y - area.y, area.y - e.size.y);
+            } else if is_horizontal_paddle(e.kind) {
+                e.position.x = clamp(e.position.x, e.size.x - area.x, area.x - e.size.x);
+            }
         }
     }
+    clamp_to_zones(game);
+    hold_balls(game);
 
-    game.ball.position += game.ball.velocity;
+    for (id, obstacle) in game.obstacles.iter() {
+        let e = match game.entities.get_mut(*id) {
+            Some(e) => { e }
+            None => { continue; }
+        };
+        let (low, high) = (obstacle.position - obstacle.travel, obstacle.position + obstacle.travel);
+        let position = Vector2::new(reflect(e.position.x, low.x, high.x),
+                                    reflect(e.position.y, low.y, high.y));
+        if position.x != e.position.x {
+            e.velocity.x *= -1;
+        }
+        if position.y != e.position.y {
+            e.velocity.y *= -1;
+        }
+        // Reflecting can still overshoot travel shorter than a tick's movement
+        e.position = Vector2::new(clamp(position.x, low.x, high.x), clamp(position.y, low.y, high.y));
+    }
 
-    let goal_x = game.cfg.area.x + game.cfg.ball_size;
-    let goal = if game.ball.position.x > goal_x {
-        Some(Player::Left)
-    } else if game.ball.position.x < -goal_x {
-        Some(Player::Right)
-    } else {
-        None
+    if let Some(ref multiball) = game.cfg.multiball.clone() {
+        if multiball.interval > 0 && game.tick % multiball.interval == 0 {
+            spawn_extra_ball(game, multiball, callback);
+        }
+    }
+
+    if let Some(ref power_ups) = game.cfg.power_ups.clone() {
+        if power_ups.interval > 0 && game.tick % power_ups.interval == 0 {
+            spawn_power_up(game, power_ups, callback);
+        }
+    }
+
+    with_mode(game, |mode, game| mode.on_tick(game, &mut *callback));
+
+    for id in game.entities.ids(Entity::Ball) {
+        if game.is_over() {
+            break;
+        }
+        if game.catches.contains_key(&id) {
+            continue;
+        }
+        advance_ball(game, id, callback);
+    }
+    if game.is_over() {
+        for id in game.entities.ids(Entity::Ball) {
+            end_rally(game, id, callback);
+        }
+    }
+    collide_balls(game);
+    update_difficulty(game, callback);
+
+    // Only what moved this tick, counting entities spawned during it
+    for (&id, e) in game.entities.iter() {
+        if positions.get(&id) != Some(&e.position) {
+            callback(Event::Move{ id: id, x: e.position.x, y: e.position.y });
+        }
+    }
+
+    Ok(())
+}
+
+fn advance_ball<F>(game: &mut Game, id: ID, callback: &mut F) where F: FnMut(Event) {
+    let mut ball = match game.entities.get(id) {
+        Some(ball) => { ball.clone() }
+        None => { return; }
     };
 
-    if let Some(player) = goal {
-        game.ball.position = Vector2 { x: 0, y: 0 };
-        let score = {
-            let player_data = game.get_player(&player);
-            player_data.score += 1;
-            player_data.score
-        };
-        game.state = State::Ready;
-        callback(Event::Goal{player: player, score: score});
+    let area = game.cfg.area;
+    let goal = Vector2::new(area.x + ball.size.x, area.y + ball.size.y);
+    let opening = game.cfg.goal_opening.unwrap_or(i64::max_value());
+    let open = |player| {
+        let along = if is_vertical_wall(player) { ball.position.y } else { ball.position.x };
+        game.defends(player) && along.abs() < opening
+    };
+    let walls = [(Player::Left, ball.position.x < -goal.x),
+                 (Player::Right, ball.position.x > goal.x),
+                 (Player::Bottom, ball.position.y < -goal.y),
+                 (Player::Top, ball.position.y > goal.y)];
+    let conceding = walls.iter()
+        .find(|&&(player, crossed)| crossed && open(player))
+        .map(|&(player, _)| player);
+
+    if let Some(player) = conceding {
+        concede_goal(game, id, player, callback);
+        return;
+    }
+
+    // Walls act on the ball as configured unless it is heading into a defended goal
+    let hit_walls: Vec<(Player, WallBehaviour)> = [(Player::Left, ball.position.x < -area.x),
+                                                   (Player::Right, ball.position.x > area.x),
+                                                   (Player::Bottom, ball.position.y < -area.y),
+                                                   (Player::Top, ball.position.y > area.y)]
+        .iter()
+        .filter(|&&(player, crossed)| crossed && !open(player))
+        .map(|&(player, _)| (player, game.cfg.walls.behaviour(player)))
+        .collect();
+
+    for (player, behaviour) in hit_walls {
+        let vertical = is_vertical_wall(player);
+        match behaviour {
+            WallBehaviour::Reflect if vertical => {
+                ball.position.x = reflect(ball.position.x, -area.x, area.x);
+                ball.velocity.x *= -1;
+                game.spins.remove(&id);
+            }
+            WallBehaviour::Reflect => {
+                ball.position.y = reflect(ball.position.y, -area.y, area.y);
+                ball.velocity.y *= -1;
+                game.spins.remove(&id);
+            }
+            WallBehaviour::Wrap if vertical => {
+                ball.position.x -= 2 * area.x * ball.position.x.signum();
+            }
+            WallBehaviour::Wrap => {
+                ball.position.y -= 2 * area.y * ball.position.y.signum();
+            }
+            WallBehaviour::Absorb => {
+                // The last player to hit the ball gives away the point
+                let owner = game.owners.get(&id).cloned().filter(|&owner| game.defends(owner));
+                match owner {
+                    Some(owner) => { concede_goal(game, id, owner, callback); }
+                    None => { end_rally(game, id, callback); }
+                }
+                return;
+            }
+        }
+    }
+
+    // Paddles hit balls coming at their face, and ones they catch up with in their zone
+    let (vertical_collision, horizontal_collision) = {
+        let collides = |vertical: bool| game.entities.iter()
+            .find(|&(_, e)| {
+                let closing = if vertical {
+                    (ball.velocity.x - e.velocity.x) * forward_sign(e.kind)
+                } else {
+                    (ball.velocity.y - e.velocity.y) * forward_sign(e.kind)
+                };
+                let facing = if vertical { is_vertical_paddle(e.kind) } else { is_horizontal_paddle(e.kind) };
+                facing && closing < 0 && ball.overlaps(e)
+            })
+            .map(|(&paddle, e)| (paddle, e.velocity));
+        (collides(true), collides(false))
+    };
+
+    // Bounces are mirrored in the frame of the moving paddle
+    if let Some((_, velocity)) = vertical_collision {
+        ball.velocity.x = 2 * velocity.x - ball.velocity.x;
+    }
+    if let Some((_, velocity)) = horizontal_collision {
+        ball.velocity.y = 2 * velocity.y - ball.velocity.y;
+    }
+
+    // Paddles moving along their wall curve the ball in the direction they move
+    let curve = game.cfg.field.as_ref().map(|field| field.curve).unwrap_or(0);
+    if let Some((_, velocity)) = vertical_collision {
+        game.spins.insert(id, Vector2::new(0, velocity.y) * curve / 1000);
+    } else if let Some((_, velocity)) = horizontal_collision {
+        game.spins.insert(id, Vector2::new(velocity.x, 0) * curve / 1000);
+    }
+
+    // Obstacles and bricks send the ball away from their center along the axis it hit them on
+    let solids: Vec<(ID, EntityData)> = game.entities.iter()
+        .filter(|&(_, e)| (e.kind == Entity::Obstacle || e.kind == Entity::Brick) && ball.overlaps(e))
+        .map(|(&solid, e)| (solid, e.clone()))
+        .collect();
+    for (solid, e) in solids {
+        game.spins.remove(&id);
+        if e.kind == Entity::Brick {
+            hit_brick(game, solid, callback);
+        }
+        let diff = ball.position - e.position;
+        let overlap_x = ball.size.x + e.size.x - diff.x.abs();
+        let overlap_y = ball.size.y + e.size.y - diff.y.abs();
+        if overlap_x < overlap_y {
+            ball.velocity.x = if diff.x < 0 { -ball.velocity.x.abs() } else { ball.velocity.x.abs() };
+        } else {
+            ball.velocity.y = if diff.y < 0 { -ball.velocity.y.abs() } else { ball.velocity.y.abs() };
+        }
+    }
+
+    let hit = vertical_collision.or(horizontal_collision)
+        .map(|(paddle, _)| paddle)
+        .and_then(|paddle| game.players.iter().find(|p| p.paddle == Some(paddle)))
+        .map(|p| (p.player, p.paddle.unwrap()));
+    if let Some((player, paddle)) = hit {
+        game.owners.insert(id, player);
+        hit_with_ability(game, id, &mut ball, player, paddle);
+        with_mode(game, |mode, game| mode.on_hit(game, id, player, &mut *callback));
+    }
+
+    let collected: Vec<ID> = game.power_ups.keys()
+        .cloned()
+        .filter(|&power_up| ball.overlaps(game.entities.get(power_up).unwrap()))
+        .collect();
+    // The mode may have taken the ball out of play
+    match game.entities.get_mut(id) {
+        Some(e) => { *e = ball; }
+        None => { return; }
+    }
+
+    // Balls nobody has hit yet pass over power-ups
+    if let Some(&player) = game.owners.get(&id) {
+        for power_up in collected {
+            collect_power_up(game, power_up, player, callback);
+        }
+    }
+}
+
+fn concede_goal<F>(game: &mut Game, id: ID, player: Player, callback: &mut F) where F: FnMut(Event) {
+    if !with_mode(game, |mode, game| mode.on_goal(game, id, player, &mut *callback)) {
+        return;
+    }
+
+    end_rally(game, id, callback);
+
+    if let Some(ref multiball) = game.cfg.multiball.clone() {
+        let goals = game.goals();
+        if multiball.score_interval > 0 && goals % multiball.score_interval as u32 == 0 {
+            spawn_extra_ball(game, multiball, callback);
+        }
+    }
+}
+
+fn hit_brick<F>(game: &mut Game, id: ID, callback: &mut F) where F: FnMut(Event) {
+    let destroyed = match game.bricks.get_mut(&id) {
+        Some(hit_points) => {
+            // Bricks built with no hit points go with the first hit
+            *hit_points = hit_points.saturating_sub(1);
+            *hit_points == 0
+        }
+        None => { return; }
+    };
+    if destroyed {
+        game.destroy(id, callback);
+        callback(Event::BrickDestroyed { id: id });
+    }
+}
+
+// The round only ends with the last ball, extra balls are removed
+fn end_rally<F>(game: &mut Game, id: ID, callback: &mut F) where F: FnMut(Event) {
+    let balls = game.entities.ids(Entity::Ball);
+    if !balls.contains(&id) {
+        // Already destroyed by the mode
+        if balls.is_empty() {
+            game.state = State::Ready;
+        }
+        return;
+    }
+    if balls.len() > 1 {
+        game.destroy(id, callback);
     } else {
-        let ball_position_y = reflect(game.ball.position.y, -game.cfg.area.y, game.cfg.area.y);
+        game.entities.get_mut(id).unwrap().position = Vector2 { x: 0, y: 0 };
+        game.owners.remove(&id);
+        game.spins.remove(&id);
+        game.catches.remove(&id);
+        game.state = State::Ready;
+    }
+}
+
+fn spawn_extra_ball<F>(game: &mut Game, multiball: &MultiballConfiguration, callback: &mut F) where F: FnMut(Event) {
+    let balls = game.entities.ids(Entity::Ball).len();
+    if balls >= multiball.max_balls {
+        return;
+    }
+
+    // Alternate serve directions so that consecutive balls spread out
+    let velocity = Vector2::new(
+        if balls % 2 == 0 { 240 } else { -240 },
+        if balls / 2 % 2 == 0 { 240 } else { -240 });
+    game.spawn(EntityData {
+        kind: Entity::Ball,
+        size: Vector2::new(game.cfg.ball_size, game.cfg.ball_size),
+        position: Vector2::new(0, 0),
+        velocity: velocity
+    }, callback);
+}
 
-        if ball_position_y != game.ball.position.y {
-            game.ball.velocity.y *= -1;
+fn spawn_power_up<F>(game: &mut Game, power_ups: &PowerUpConfiguration, callback: &mut F) where F: FnMut(Event) {
+    if game.power_ups.len() >= power_ups.max_power_ups {
+        return;
+    }
+
+    let area = game.cfg.area;
+    let position = Vector2::new(game.rng.range(-area.x / 2, area.x / 2),
+                                game.rng.range(power_ups.size - area.y, area.y - power_ups.size));
+    let power_up = POWER_UPS[game.rng.range(0, POWER_UPS.len() as i64) as usize];
+    let id = game.spawn(EntityData {
+        kind: Entity::PowerUp,
+        size: Vector2::new(power_ups.size, power_ups.size),
+        position: position,
+        velocity: Vector2::new(0, 0)
+    }, callback);
+    game.power_ups.insert(id, power_up);
+    callback(Event::PowerUpSpawned { id: id, power_up: power_up });
+}
+
+fn collect_power_up<F>(game: &mut Game, id: ID, player: Player, callback: &mut F) where F: FnMut(Event) {
+    let power_up = game.power_ups[&id];
+    game.destroy(id, callback);
+    callback(Event::PowerUpCollected { id: id, player: player, power_up: power_up });
+
+    let targets: Vec<Player> = match power_up {
+        PowerUp::Shrink | PowerUp::ReverseControls => {
+            game.players.iter().map(|p| p.player).filter(|p| p.side() != player.side()).collect()
         }
-        game.ball.position.y = ball_position_y;
+        _ => { vec![player] }
+    };
+    let duration = game.cfg.power_ups.as_ref().map(|power_ups| power_ups.duration).unwrap_or(0);
+    let reversed = reversed_players(game);
+    game.effects.push(Effect {
+        power_up: power_up,
+        player: player,
+        targets: targets.clone(),
+        expires: game.tick + duration
+    });
+    update_paddles(game, &targets, &reversed, callback);
+}
 
-        let paddle_collision = {
-            let diff = game.ball.position - if game.ball.velocity.x < 0 {
-                game.left.position
-            } else {
-                game.right.position
-            };
+// Ends the effects expiring at or before the given tick
+fn end_effects<F>(game: &mut Game, tick: u64, callback: &mut F) where F: FnMut(Event) {
+    let reversed = reversed_players(game);
+    let (expired, active): (Vec<Effect>, Vec<Effect>) = game.effects.drain(..).partition(|e| e.expires <= tick);
+    game.effects = active;
 
-            diff.x.abs() < game.cfg.paddle.x + game.cfg.ball_size
-                && diff.y.abs() < game.cfg.paddle.y + game.cfg.ball_size
+    let mut targets = Vec::new();
+    for effect in expired {
+        callback(Event::PowerUpExpired { player: effect.player, power_up: effect.power_up });
+        for target in effect.targets {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    update_paddles(game, &targets, &reversed, callback);
+}
+
+// Resizes the players' paddles to their current effects and the difficulty
+// rules, and turns around the paddles of players whose controls were reversed
+// or restored
+fn update_paddles<F>(game: &mut Game, players: &[Player], reversed: &[Player], callback: &mut F) where F: FnMut(Event) {
+    let reversed_now = reversed_players(game);
+    for &player in players {
+        let scale = game.effects.iter()
+            .filter(|e| e.targets.contains(&player))
+            .fold(difficulty_scale(game, player), |scale, e| match e.power_up {
+                PowerUp::Grow => { scale * 3 / 2 }
+                PowerUp::Shrink => { scale / 2 }
+                _ => { scale }
+            });
+        let flip = reversed.contains(&player) != reversed_now.contains(&player);
+        let size = paddle_data(&game.cfg, player, 0).size;
+        let paddle = match game.get_player(&player).and_then(|p| p.paddle) {
+            Some(paddle) => { paddle }
+            None => { continue; }
         };
+        if let Some(p) = game.entities.get_mut(paddle) {
+            let old_size = p.size;
+            if is_horizontal_paddle(p.kind) {
+                p.size.x = size.x * scale / 1000;
+            } else {
+                p.size.y = size.y * scale / 1000;
+            }
+            if p.size != old_size {
+                callback(Event::Resize { id: paddle, w: p.size.x, h: p.size.y });
+            }
+            if flip {
+                p.velocity = p.velocity * -1;
+            }
+        }
+    }
+}
+
+// Resizes every paddle to the scores, after they may have changed
+fn update_difficulty<F>(game: &mut Game, callback: &mut F) where F: FnMut(Event) {
+    if game.cfg.difficulty.is_none() {
+        return;
+    }
+    let players = game.players();
+    let reversed = reversed_players(game);
+    update_paddles(game, &players, &reversed, callback);
+}
+
+// Thousandths of their length that the player's paddle is scaled to for the scores
+fn difficulty_scale(game: &Game, player: Player) -> i64 {
+    let difficulty = match game.cfg.difficulty {
+        Some(ref difficulty) => { difficulty }
+        None => { return 1000; }
+    };
+    let score = game.score(player) as i64;
+    let leader = game.players.iter().map(|p| game.score(p.player) as i64).max().unwrap_or(0);
+    clamp(1000 - difficulty.shrink * score + difficulty.rubber_band * (leader - score),
+          difficulty.min_scale, difficulty.max_scale)
+}
+
+fn reversed_players(game: &Game) -> Vec<Player> {
+    game.effects.iter()
+        .filter(|e| e.power_up == PowerUp::ReverseControls)
+        .flat_map(|e| e.targets.iter().cloned())
+        .collect()
+}
+
+// Percentage of their velocity that balls move each tick
+fn ball_speed(game: &Game) -> i64 {
+    game.effects.iter().fold(100, |speed, e| match e.power_up {
+        PowerUp::BallSpeed => { speed * 3 / 2 }
+        PowerUp::SlowMotion => { speed / 2 }
+        _ => { speed }
+    })
+}
+
+fn apply_forces(game: &mut Game) {
+    let field = match game.cfg.field {
+        Some(ref field) => { field.clone() }
+        None => { return; }
+    };
 
-        if paddle_collision {
-            game.ball.velocity.x *= -1;
+    let tick = game.tick;
+    let mut force = field.gravity;
+    if let Some(ref wind) = field.wind {
+        if wind.period > 0 && tick % wind.period < wind.duration {
+            if tick / wind.period % 2 == 0 {
+                force += wind.force;
+            } else {
+                force -= wind.force;
+            }
         }
     }
 
-    callback(Event::Move{ id: game.left.id, x: game.left.position.x, y:  game.left.position.y });
-    callback(Event::Move{ id: game.right.id, x: game.right.position.x, y: game.right.position.y });
-    callback(Event::Move{ id: game.ball.id, x: game.ball.position.x, y: game.ball.position.y });
+    for id in game.entities.ids(Entity::Ball) {
+        let acceleration = force + game.spins.get(&id).cloned().unwrap_or(Vector2::new(0, 0));
+        let ball = game.entities.get_mut(id).unwrap();
+        ball.velocity += Vector2::new(per_tick(acceleration.x, tick), per_tick(acceleration.y, tick));
+    }
+}
 
-    Ok(())
+// Spreads an acceleration over 1000 ticks so that the whole ticks add up to it
+// exactly, with any remainder carried over to the next ones
+fn per_tick(acceleration: i64, tick: u64) -> i64 {
+    let t = tick as i64;
+    acceleration * t / 1000 - acceleration * (t - 1) / 1000
 }
 
-fn action_move<F>(game: &mut Game, player: Player, direction: Direction, callback: F) -> Result<(), Error> where F: FnMut(Event) {
+// Balls have equal mass, so an elastic collision swaps their velocities.
+// Balls that are already separating are left alone so they cannot get stuck.
+fn collide_balls(game: &mut Game) {
+    let balls: Vec<ID> = game.entities.ids(Entity::Ball).into_iter()
+        .filter(|id| !game.catches.contains_key(id))
+        .collect();
+    for (i, &a) in balls.iter().enumerate() {
+        for &b in balls[i + 1..].iter() {
+            let (ball_a, ball_b) = (game.entities.get(a).unwrap().clone(), game.entities.get(b).unwrap().clone());
+            let diff = ball_b.position - ball_a.position;
+            let closing = ball_b.velocity - ball_a.velocity;
+            if ball_a.overlaps(&ball_b) && diff.x * closing.x + diff.y * closing.y < 0 {
+                game.entities.get_mut(a).unwrap().velocity = ball_b.velocity;
+                game.entities.get_mut(b).unwrap().velocity = ball_a.velocity;
+            }
+        }
+    }
+}
+
+fn action_move<F>(game: &mut Game, t: u64, player: Player, control: Control, callback: F) -> Result<(), Error> where F: FnMut(Event) {
     try!(game.require_initialized());
-    let p = match player {
-        Player::Left => { &mut game.left }
-        Player::Right => { &mut game.right }
+    if game.get_player(&player).is_none() {
+        return Err(Error::UnknownPlayer);
+    }
+    let position = game.inputs.iter().position(|input| input.t > t).unwrap_or(game.inputs.len());
+    game.inputs.insert(position, Input { t: t, player: player, control: control });
+    Ok(())
+}
+
+fn write_control(hasher: &mut StateHasher, control: Control) {
+    match control {
+        Control::Axis(along, forward) => {
+            hasher.write_u8(0);
+            hasher.write_i64(along);
+            hasher.write_i64(forward);
+        }
+        Control::Target(target) => {
+            hasher.write_u8(1);
+            hasher.write_i64(target);
+        }
+        Control::Ability => { hasher.write_u8(2); }
+    }
+}
+
+fn direction_axes(direction: Direction) -> (i64, i64) {
+    match direction {
+        Direction::Up => { (1000, 0) }
+        Direction::Neutral => { (0, 0) }
+        Direction::Down => { (-1000, 0) }
+        Direction::Forward => { (0, 1000) }
+        Direction::Back => { (0, -1000) }
+        Direction::UpForward => { (1000, 1000) }
+        Direction::UpBack => { (1000, -1000) }
+        Direction::DownForward => { (-1000, 1000) }
+        Direction::DownBack => { (-1000, -1000) }
+    }
+}
+
+fn value_axis(value: f64) -> i64 {
+    if value.is_nan() {
+        return 0;
+    }
+    (value.max(-1.0).min(1.0) * 1000.0).round() as i64
+}
+
+fn apply_inputs<F>(game: &mut Game, t: u64, callback: &mut F) where F: FnMut(Event) {
+    while !game.inputs.is_empty() && game.inputs[0].t <= t {
+        let input = game.inputs.remove(0);
+        if let Control::Ability = input.control {
+            use_ability(game, input.player, callback);
+            continue;
+        }
+        let reversed = reversed_players(game).contains(&input.player);
+        let physics = game.cfg.paddle_physics.is_some();
+        let zone = game.cfg.paddle_zone.is_some();
+        let max_speed = game.cfg.paddle_speed(input.player);
+        let paddle = match game.get_player(&input.player) {
+            Some(p) => {
+                p.control = input.control;
+                p.paddle
+            }
+            None => { None }
+        };
+        // Other paddles are steered every tick in steer_paddles
+        let (along, forward) = match input.control {
+            Control::Axis(along, forward) if !physics => { (along, if zone { forward } else { 0 }) }
+            _ => { continue; }
+        };
+        if let Some(p) = paddle.and_then(|paddle| game.entities.get_mut(paddle)) {
+            let sign = if reversed { -1 } else { 1 };
+            let speed = max_speed * along / 1000 * sign;
+            let forward_speed = max_speed * forward / 1000 * sign * forward_sign(p.kind);
+            if is_horizontal_paddle(p.kind) {
+                p.velocity = Vector2::new(speed, forward_speed);
+            } else {
+                p.velocity = Vector2::new(forward_speed, speed);
+            }
+        }
+    }
+}
+
+// Paddles head for their targets, and with paddle physics every paddle speeds
+// up and slows down gradually. Reversed controls mirror the targets.
+fn steer_paddles(game: &mut Game) {
+    let physics = game.cfg.paddle_physics.clone();
+    let zone = game.cfg.paddle_zone.is_some();
+    let area = game.cfg.area;
+    let reversed = reversed_players(game);
+    for i in 0..game.players.len() {
+        let sign = if reversed.contains(&game.players[i].player) { -1 } else { 1 };
+        let control = game.players[i].control;
+        let max_speed = game.cfg.paddle_speed(game.players[i].player);
+        // Dashing paddles cover the distance to their target in fewer ticks
+        let dash = if active_ability(game, &game.players[i]) == Some(Ability::Dash) { DASH_SPEED } else { 1 };
+        if let Some(p) = game.players[i].paddle.and_then(|paddle| game.entities.get_mut(paddle)) {
+            let horizontal = is_horizontal_paddle(p.kind);
+            let forward_sign = forward_sign(p.kind);
+            let (position, along, forward) = if horizontal {
+                (p.position.x, p.velocity.x, p.velocity.y * forward_sign)
+            } else {
+                (p.position.y, p.velocity.y, p.velocity.x * forward_sign)
+            };
+            // None once the input is released
+            let (wanted_along, wanted_forward) = match control {
+                Control::Axis(along, forward) => {
+                    (if along != 0 { Some(max_speed * along * sign / 1000) } else { None },
+                     if forward != 0 && zone { Some(max_speed * forward * sign / 1000) } else { None })
+                }
+                Control::Target(target) => {
+                    // Kept to where the paddle can get to at its current size
+                    let reach = if horizontal { area.x - p.size.x } else { area.y - p.size.y };
+                    let target = clamp(target, -reach, reach) * sign;
+                    (Some(clamp((target - position) / dash, -max_speed, max_speed)), None)
+                }
+                Control::Ability => { unreachable!() }
+            };
+            let (along, forward) = match (physics.as_ref(), control) {
+                (None, Control::Target(_)) => { (wanted_along.unwrap_or(0), 0) }
+                (None, _) => { continue; }
+                (Some(physics), _) => {
+                    (accelerate(along, wanted_along, physics), accelerate(forward, wanted_forward, physics))
+                }
+            };
+            if horizontal {
+                p.velocity = Vector2::new(along, forward * forward_sign);
+            } else {
+                p.velocity = Vector2::new(forward * forward_sign, along);
+            }
+        }
+    }
+}
+
+fn accelerate(velocity: i64, wanted: Option<i64>, physics: &PaddlePhysicsConfiguration) -> i64 {
+    match wanted {
+        Some(wanted) => { velocity + clamp(wanted - velocity, -physics.acceleration, physics.acceleration) }
+        None => { velocity - clamp(velocity, -physics.friction, physics.friction) }
+    }
+}
+
+// Abilities used while cooling down are ignored
+fn use_ability<F>(game: &mut Game, player: Player, callback: &mut F) where F: FnMut(Event) {
+    let ability = match game.cfg.abilities.iter().find(|a| a.player == player) {
+        Some(ability) => { ability.clone() }
+        None => { return; }
     };
+    let tick = game.tick;
+    match game.get_player(&player) {
+        Some(p) if p.ability_ready <= tick => {
+            p.ability_ready = tick + ability.cooldown;
+            p.ability_until = tick + ability.duration;
+        }
+        _ => { return; }
+    }
+    callback(Event::AbilityUsed { player: player, ability: ability.ability });
+}
 
-    p.velocity.y = match direction {
-        Direction::Up => { 300 }
-        Direction::Neutral => { 0 }
-        Direction::Down => { -300 }
+fn active_ability(game: &Game, player_data: &PlayerData) -> Option<Ability> {
+    if game.tick > player_data.ability_until {
+        return None;
+    }
+    game.cfg.abilities.iter().find(|a| a.player == player_data.player).map(|a| a.ability)
+}
+
+// Catch and Smash are used up by the hit
+fn hit_with_ability(game: &mut Game, id: ID, ball: &mut EntityData, player: Player, paddle: ID) {
+    let ability = game.players.iter().find(|p| p.player == player).and_then(|p| active_ability(game, p));
+    match ability {
+        Some(Ability::Catch) => {
+            let offset = ball.position - game.entities.get(paddle).unwrap().position;
+            game.catches.insert(id, Catch {
+                paddle: paddle,
+                offset: offset,
+                velocity: ball.velocity,
+                release: game.tick + CATCH_TICKS
+            });
+            ball.velocity = Vector2::new(0, 0);
+        }
+        Some(Ability::Smash) => { ball.velocity = ball.velocity * SMASH_SPEED / 100; }
+        _ => { return; }
+    }
+    game.get_player(&player).unwrap().ability_until = 0;
+}
+
+// Caught balls move along with their paddle, and are let go with the paddle's
+// movement along its wall added to their velocity
+fn hold_balls(game: &mut Game) {
+    let tick = game.tick;
+    for (id, catch) in game.catches.clone() {
+        let paddle = game.entities.get(catch.paddle).cloned();
+        let ball = game.entities.get_mut(id).unwrap();
+        ball.velocity = Vector2::new(0, 0);
+        if let Some(ref paddle) = paddle {
+            ball.position = paddle.position + catch.offset;
+        }
+        if tick >= catch.release || paddle.is_none() {
+            let aim = match paddle {
+                Some(ref paddle) if is_horizontal_paddle(paddle.kind) => { Vector2::new(paddle.velocity.x, 0) }
+                Some(ref paddle) => { Vector2::new(0, paddle.velocity.y) }
+                None => { Vector2::new(0, 0) }
+            };
+            ball.velocity = catch.velocity + aim;
+            game.catches.remove(&id);
+        }
+    }
+}
+
+// Keeps paddles between their goal line and the edge of their zone
+fn clamp_to_zones(game: &mut Game) {
+    let depth = match game.cfg.paddle_zone {
+        Some(depth) => { depth }
+        None => { return; }
     };
-    Ok(())
+    let mode = game.mode.as_ref().unwrap();
+    let entities = &mut game.entities;
+    for player_data in game.players.iter() {
+        let line = paddle_data(&game.cfg, player_data.player, mode.paddle_offset(player_data.player)).position;
+        if let Some(p) = player_data.paddle.and_then(|paddle| entities.get_mut(paddle)) {
+            let edge = line + forward_vector(p.kind) * depth;
+            if is_horizontal_paddle(p.kind) {
+                let y = clamp(p.position.y, line.y.min(edge.y), line.y.max(edge.y));
+                if y != p.position.y {
+                    p.position.y = y;
+                    p.velocity.y = 0;
+                }
+            } else {
+                let x = clamp(p.position.x, line.x.min(edge.x), line.x.max(edge.x));
+                if x != p.position.x {
+                    p.position.x = x;
+                    p.velocity.x = 0;
+                }
+            }
+        }
+    }
+}
+
+// Towards the center from the paddle's goal line
+fn forward_sign(kind: Entity) -> i64 {
+    match kind {
+        Entity::RightPaddle | Entity::TopPaddle => { -1 }
+        _ => { 1 }
+    }
+}
+
+fn forward_vector(kind: Entity) -> Vector2<i64> {
+    if is_horizontal_paddle(kind) {
+        Vector2::new(0, forward_sign(kind))
+    } else {
+        Vector2::new(forward_sign(kind), 0)
+    }
 }
 
 fn clamp<T: Ord>(x: T, a: T, b: T) -> T {
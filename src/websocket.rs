@@ -0,0 +1,328 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use ws;
+use rustc_serialize::json::{ Json, ToJson };
+use super::*;
+use host::Host;
+
+// Actions are sent by clients as JSON objects tagged with "action", e.g.
+//   { "action": "move", "t": 30000, "player": "left", "direction": "up" }
+//   { "action": "move_axis", "t": 30000, "player": "left", "value": -0.5 }
+//   { "action": "move_to", "t": 30000, "player": "left", "y": 120000 }
+//   { "action": "ability", "t": 30000, "player": "left" }
+// Events are broadcast to every client as JSON objects tagged with "event", e.g.
+//   { "event": "move", "id": 2, "x": 240, "y": -480 }
+// Rejected messages are answered only to the sender with { "error": "..." }.
+// On connect each client is told its role with
+//   { "event": "joined", "player": "left", "host": true }
+// where "player" is null for spectators, followed by create events for the
+// entities of an already initialized game and resize events for its paddles.
+
+const MAX_INPUTS_PER_SECOND: u32 = 20;
+
+pub struct Gateway {
+    host: Rc<RefCell<Host>>
+}
+
+pub struct Connection {
+    out: ws::Sender,
+    host: Rc<RefCell<Host>>
+}
+
+impl ws::Factory for Gateway {
+    type Handler = Connection;
+
+    fn connection_made(&mut self, out: ws::Sender) -> Connection {
+        Connection { out: out, host: self.host.clone() }
+    }
+}
+
+impl ws::Handler for Connection {
+    fn on_open(&mut self, _: ws::Handshake) -> ws::Result<()> {
+        let peer = self.out.connection_id();
+        let (player, is_host, entities) = {
+            let mut host = self.host.borrow_mut();
+            let player = host.join(peer);
+            let entities = match host.game().state() {
+                State::Uninitialized => { Vec::new() }
+                _ => { host.game().entities() }
+            };
+            (player, host.is_host(peer), entities)
+        };
+
+        let mut obj = BTreeMap::new();
+        obj.insert("event".to_string(), "joined".to_json());
+        obj.insert("player".to_string(), match player {
+            Some(player) => { player_to_str(&player).to_json() }
+            None => { Json::Null }
+        });
+        obj.insert("host".to_string(), is_host.to_json());
+        try!(self.out.send(Json::Object(obj).to_string()));
+
+        for e in entities.iter() {
+            let create = Event::Create { id: e.id, entity: e.entity, x: e.position.x, y: e.position.y };
+            try!(self.out.send(event_to_json(create).to_string()));
+        }
+        for e in entities.iter().filter(|e| is_vertical_paddle(e.entity) || is_horizontal_paddle(e.entity)) {
+            let resize = Event::Resize { id: e.id, w: e.size.x, h: e.size.y };
+            try!(self.out.send(event_to_json(resize).to_string()));
+        }
+        Ok(())
+    }
+
+    fn on_close(&mut self, _: ws::CloseCode, _: &str) {
+        self.host.borrow_mut().leave(self.out.connection_id());
+    }
+
+    fn on_message(&mut self, msg: ws::Message) -> ws::Result<()> {
+        let action = match msg.as_text().ok().and_then(|text| Json::from_str(text).ok()) {
+            Some(json) => { action_from_json(&json) }
+            None => { None }
+        };
+
+        let action = match action {
+            Some(action) => { action }
+            None => { return self.out.send(error_to_json("InvalidMessage").to_string()); }
+        };
+
+        let mut events = Vec::new();
+        let result = self.host.borrow_mut().process(self.out.connection_id(), action, |e| events.push(e));
+        match result {
+            Ok(()) => {
+                for e in events {
+                    try!(self.out.broadcast(event_to_json(e).to_string()));
+                }
+                Ok(())
+            }
+            Err(e) => { self.out.send(error_to_json(&format!("{:?}", e)).to_string()) }
+        }
+    }
+}
+
+pub fn server(cfg: GameConfiguration) -> ws::Result<ws::WebSocket<Gateway>> {
+    let host = Host::new(cfg, MAX_INPUTS_PER_SECOND);
+    ws::WebSocket::new(Gateway { host: Rc::new(RefCell::new(host)) })
+}
+
+pub fn listen(addr: &str, cfg: GameConfiguration) -> ws::Result<()> {
+    try!(try!(server(cfg)).listen(addr));
+    Ok(())
+}
+
+pub fn action_from_json(json: &Json) -> Option<Action> {
+    let field = |name: &str| json.find(name);
+    match field("action").and_then(|a| a.as_string()) {
+        Some("initialize") => { Some(Action::Initialize) }
+        Some("start") => { Some(Action::Start) }
+        Some("reset") => {
+            field("seed").and_then(|s| s.as_i64()).map(|seed| Action::Reset { seed: seed })
+        }
+        Some("time") => {
+            field("t").and_then(|t| t.as_u64()).map(|t| Action::Time { t: t })
+        }
+        Some("move") => {
+            let t = field("t").and_then(|t| t.as_u64());
+            let player = field("player").and_then(|p| p.as_string()).and_then(player_from_str);
+            let direction = match field("direction").and_then(|d| d.as_string()) {
+                Some("up") => { Some(Direction::Up) }
+                Some("neutral") => { Some(Direction::Neutral) }
+                Some("down") => { Some(Direction::Down) }
+                Some("forward") => { Some(Direction::Forward) }
+                Some("back") => { Some(Direction::Back) }
+                Some("up_forward") => { Some(Direction::UpForward) }
+                Some("up_back") => { Some(Direction::UpBack) }
+                Some("down_forward") => { Some(Direction::DownForward) }
+                Some("down_back") => { Some(Direction::DownBack) }
+                _ => { None }
+            };
+            match (t, player, direction) {
+                (Some(t), Some(player), Some(direction)) => {
+                    Some(Action::Move { t: t, player: player, direction: direction })
+                }
+                _ => { None }
+            }
+        }
+        Some("move_axis") => {
+            let t = field("t").and_then(|t| t.as_u64());
+            let player = field("player").and_then(|p| p.as_string()).and_then(player_from_str);
+            let value = field("value").and_then(|v| v.as_f64());
+            match (t, player, value) {
+                (Some(t), Some(player), Some(value)) => {
+                    Some(Action::MoveAxis { t: t, player: player, value: value })
+                }
+                _ => { None }
+            }
+        }
+        Some("move_to") => {
+            let t = field("t").and_then(|t| t.as_u64());
+            let player = field("player").and_then(|p| p.as_string()).and_then(player_from_str);
+            let y = field("y").and_then(|y| y.as_i64());
+            match (t, player, y) {
+                (Some(t), Some(player), Some(y)) => {
+                    Some(Action::MoveTo { t: t, player: player, y: y })
+                }
+                _ => { None }
+            }
+        }
+        Some("ability") => {
+            let t = field("t").and_then(|t| t.as_u64());
+            let player = field("player").and_then(|p| p.as_string()).and_then(player_from_str);
+            match (t, player) {
+                (Some(t), Some(player)) => { Some(Action::Ability { t: t, player: player }) }
+                _ => { None }
+            }
+        }
+        _ => { None }
+    }
+}
+
+pub fn event_to_json(event: Event) -> Json {
+    let mut obj = BTreeMap::new();
+    let name = match event {
+        Event::Create { id, entity, x, y } => {
+            obj.insert("id".to_string(), id.to_json());
+            obj.insert("entity".to_string(), entity_to_str(&entity).to_json());
+            obj.insert("x".to_string(), x.to_json());
+            obj.insert("y".to_string(), y.to_json());
+            "create"
+        }
+        Event::Destroy { id } => {
+            obj.insert("id".to_string(), id.to_json());
+            "destroy"
+        }
+        Event::Move { id, x, y } => {
+            obj.insert("id".to_string(), id.to_json());
+            obj.insert("x".to_string(), x.to_json());
+            obj.insert("y".to_string(), y.to_json());
+            "move"
+        }
+        Event::Resize { id, w, h } => {
+            obj.insert("id".to_string(), id.to_json());
+            obj.insert("w".to_string(), w.to_json());
+            obj.insert("h".to_string(), h.to_json());
+            "resize"
+        }
+        Event::Goal { player, score } => {
+            obj.insert("player".to_string(), player_to_str(&player).to_json());
+            obj.insert("score".to_string(), score.points.to_json());
+            obj.insert("games".to_string(), score.games.to_json());
+            obj.insert("sets".to_string(), score.sets.to_json());
+            obj.insert("won".to_string(), won_to_str(&score.won).to_json());
+            "goal"
+        }
+        Event::LifeLost { player, lives } => {
+            obj.insert("player".to_string(), player_to_str(&player).to_json());
+            obj.insert("lives".to_string(), lives.to_json());
+            "life_lost"
+        }
+        Event::StreakEnded { player, streak } => {
+            obj.insert("player".to_string(), player_to_str(&player).to_json());
+            obj.insert("streak".to_string(), streak.to_json());
+            "streak_ended"
+        }
+        Event::BrickDestroyed { id } => {
+            obj.insert("id".to_string(), id.to_json());
+            "brick_destroyed"
+        }
+        Event::PowerUpSpawned { id, power_up } => {
+            obj.insert("id".to_string(), id.to_json());
+            obj.insert("power_up".to_string(), power_up_to_str(&power_up).to_json());
+            "power_up_spawned"
+        }
+        Event::PowerUpCollected { id, player, power_up } => {
+            obj.insert("id".to_string(), id.to_json());
+            obj.insert("player".to_string(), player_to_str(&player).to_json());
+            obj.insert("power_up".to_string(), power_up_to_str(&power_up).to_json());
+            "power_up_collected"
+        }
+        Event::PowerUpExpired { player, power_up } => {
+            obj.insert("player".to_string(), player_to_str(&player).to_json());
+            obj.insert("power_up".to_string(), power_up_to_str(&power_up).to_json());
+            "power_up_expired"
+        }
+        Event::AbilityUsed { player, ability } => {
+            obj.insert("player".to_string(), player_to_str(&player).to_json());
+            obj.insert("ability".to_string(), ability_to_str(&ability).to_json());
+            "ability_used"
+        }
+        Event::ClockTick { remaining } => {
+            obj.insert("remaining".to_string(), remaining.to_json());
+            "clock_tick"
+        }
+        Event::Reset => { "reset" }
+        Event::RoundStart => { "round_start" }
+    };
+    obj.insert("event".to_string(), name.to_json());
+    Json::Object(obj)
+}
+
+fn error_to_json(error: &str) -> Json {
+    let mut obj = BTreeMap::new();
+    obj.insert("error".to_string(), error.to_json());
+    Json::Object(obj)
+}
+
+fn player_from_str(s: &str) -> Option<Player> {
+    match s {
+        "left" => { Some(Player::Left) }
+        "right" => { Some(Player::Right) }
+        "top" => { Some(Player::Top) }
+        "bottom" => { Some(Player::Bottom) }
+        "left_forward" => { Some(Player::LeftForward) }
+        "right_forward" => { Some(Player::RightForward) }
+        _ => { None }
+    }
+}
+
+fn player_to_str(player: &Player) -> &'static str {
+    match player {
+        &Player::Left => { "left" }
+        &Player::Right => { "right" }
+        &Player::Top => { "top" }
+        &Player::Bottom => { "bottom" }
+        &Player::LeftForward => { "left_forward" }
+        &Player::RightForward => { "right_forward" }
+    }
+}
+
+fn entity_to_str(entity: &Entity) -> &'static str {
+    match entity {
+        &Entity::LeftPaddle => { "left_paddle" }
+        &Entity::RightPaddle => { "right_paddle" }
+        &Entity::TopPaddle => { "top_paddle" }
+        &Entity::BottomPaddle => { "bottom_paddle" }
+        &Entity::Ball => { "ball" }
+        &Entity::PowerUp => { "power_up" }
+        &Entity::Obstacle => { "obstacle" }
+        &Entity::Brick => { "brick" }
+    }
+}
+
+fn power_up_to_str(power_up: &PowerUp) -> &'static str {
+    match power_up {
+        &PowerUp::Grow => { "grow" }
+        &PowerUp::Shrink => { "shrink" }
+        &PowerUp::BallSpeed => { "ball_speed" }
+        &PowerUp::SlowMotion => { "slow_motion" }
+        &PowerUp::ReverseControls => { "reverse_controls" }
+        &PowerUp::InvisibleBall => { "invisible_ball" }
+    }
+}
+
+fn won_to_str(won: &Won) -> &'static str {
+    match won {
+        &Won::Point => { "point" }
+        &Won::Game => { "game" }
+        &Won::Set => { "set" }
+        &Won::Match => { "match" }
+    }
+}
+
+fn ability_to_str(ability: &Ability) -> &'static str {
+    match ability {
+        &Ability::Dash => { "dash" }
+        &Ability::Catch => { "catch" }
+        &Ability::Smash => { "smash" }
+    }
+}
@@ -0,0 +1,96 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use cgmath::Vector2;
+use pong::*;
+use common::{ CFG, started, entity };
+
+// The left player returns straight serves, so that they can use their ability
+// for as long as they like
+fn game(ability: Option<Ability>) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::Practice { pattern: ServePattern::Straight, speed: 400 };
+    cfg.abilities = ability.into_iter().map(|ability| AbilityConfiguration {
+        player: Player::Left,
+        ability: ability,
+        cooldown: 1000,
+        duration: 200
+    }).collect();
+    started(cfg)
+}
+
+fn abilities_used(game: &mut Game, action: Action) -> Vec<Ability> {
+    let mut used = Vec::new();
+    game.process(action, |e| {
+        if let Event::AbilityUsed { player: Player::Left, ability } = e {
+            used.push(ability);
+        }
+    }).unwrap();
+    used
+}
+
+#[test]
+fn dashing_paddles_move_faster() {
+    let mut game = game(Some(Ability::Dash));
+    game.process(Action::Ability { t: 0, player: Player::Left }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Time { t: 5000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 5 * 900);
+
+    // Back to normal speed once the dash is over
+    game.process(Action::Move { t: 5000, player: Player::Left, direction: Direction::Neutral }, |_| {}).unwrap();
+    game.process(Action::Move { t: 200000, player: Player::Left, direction: Direction::Down }, |_| {}).unwrap();
+    game.process(Action::Time { t: 210000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 4500 - 10 * 300);
+}
+
+#[test]
+fn abilities_cool_down() {
+    let mut game = game(Some(Ability::Dash));
+    game.process(Action::Ability { t: 0, player: Player::Left }, |_| {}).unwrap();
+    game.process(Action::Ability { t: 500000, player: Player::Left }, |_| {}).unwrap();
+    game.process(Action::Ability { t: 1000000, player: Player::Left }, |_| {}).unwrap();
+    assert_eq!(abilities_used(&mut game, Action::Time { t: 999000 }), vec![Ability::Dash]);
+    assert_eq!(abilities_used(&mut game, Action::Time { t: 1001000 }), vec![Ability::Dash]);
+
+    // Players without an ability have nothing to use
+    let mut other = self::game(None);
+    other.process(Action::Ability { t: 0, player: Player::Left }, |_| {}).unwrap();
+    assert!(abilities_used(&mut other, Action::Time { t: 1000 }).is_empty());
+}
+
+#[test]
+fn smashed_balls_come_back_faster() {
+    let mut game = game(Some(Ability::Smash));
+    game.process(Action::Ability { t: 0, player: Player::Left }, |_| {}).unwrap();
+    game.process(Action::Time { t: 100000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).velocity, Vector2::new(600, 0));
+
+    // The smash is used up by the hit
+    game.process(Action::Time { t: 250000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).velocity, Vector2::new(400, 0));
+}
+
+#[test]
+fn caught_balls_are_released_aimed_by_the_paddle() {
+    let mut game = game(Some(Ability::Catch));
+    game.process(Action::Ability { t: 0, player: Player::Left }, |_| {}).unwrap();
+    let mut t = 0;
+    while entity(&game, Entity::Ball).velocity.x != 0 {
+        assert!(t < 200000, "the ball was never caught");
+        t += 1000;
+        game.process(Action::Time { t: t }, |_| {}).unwrap();
+    }
+
+    // The ball sticks to the paddle
+    let caught = entity(&game, Entity::Ball).position;
+    game.process(Action::Move { t: t, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Time { t: t + 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).position, caught + Vector2::new(0, 3000));
+    assert_eq!(entity(&game, Entity::Ball).velocity, Vector2::new(0, 0));
+
+    game.process(Action::Time { t: t + 300000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).velocity, Vector2::new(400, 300));
+}
@@ -0,0 +1,158 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use cgmath::{ Vector2 };
+use pong::*;
+use pong::arena::ArenaError;
+use common::CFG;
+
+fn reason(text: &str) -> String {
+    match arena::parse(text, &CFG) {
+        Err(ArenaError::Invalid(reason)) => { reason }
+        other => { panic!("expected an invalid arena, got {:?}", other) }
+    }
+}
+
+#[test]
+fn arena_files_are_parsed() {
+    let obstacles = arena::parse(r#"
+        [[obstacle]]
+        position = [0, 6000]
+        size = [1000, 2000]
+
+        [[obstacle]]
+        position = [-8000, 0]
+        size = [500, 500]
+        velocity = [0, 100]
+        travel = [0, 4000]
+    "#, &CFG).unwrap();
+
+    assert_eq!(obstacles.len(), 2);
+    assert_eq!(obstacles[0].position, Vector2::new(0, 6000));
+    assert_eq!(obstacles[0].velocity, Vector2::new(0, 0));
+    assert_eq!(obstacles[1].travel, Vector2::new(0, 4000));
+    assert!(arena::parse("", &CFG).unwrap().is_empty());
+}
+
+#[test]
+fn invalid_arenas_are_rejected() {
+    match arena::parse("[[obstacle]\nposition = [0, 0]", &CFG) {
+        Err(ArenaError::Syntax(_)) => {}
+        other => { panic!("expected a syntax error, got {:?}", other) }
+    }
+    assert_eq!(reason("[[obstacle]]\nposition = [0, 6000]"), "obstacle 0: size is missing");
+    assert_eq!(reason("[[obstacle]]\nposition = [0, 6000]\nsize = [1000, 1000]\ncolor = 1"),
+               "obstacle 0: unknown key color");
+    assert_eq!(reason("[[obstacle]]\nposition = [0, 9500]\nsize = [1000, 1000]"),
+               "obstacle 0: must stay inside the area");
+    assert_eq!(reason("[[obstacle]]\nposition = [0, 1000]\nsize = [1000, 1000]"),
+               "obstacle 0: must stay clear of the center");
+    assert_eq!(reason("[[obstacle]]\nposition = [18000, 6000]\nsize = [1000, 1000]"),
+               "obstacle 0: must stay out of the way of the Right paddle");
+    assert_eq!(reason("[[obstacle]]\nposition = [0, 6000]\nsize = [1000, 1000]\nvelocity = [100, 0]"),
+               "obstacle 0: moving obstacles need travel in their direction");
+}
+
+#[test]
+fn obstacles_stay_out_of_paddle_zones() {
+    let text = "[[obstacle]]\nposition = [-8000, 6000]\nsize = [1000, 1000]";
+    assert!(arena::parse(text, &CFG).is_ok());
+    let mut cfg = CFG.clone();
+    cfg.paddle_zone = Some(12000);
+    match arena::parse(text, &cfg) {
+        Err(ArenaError::Invalid(reason)) => { assert_eq!(reason, "obstacle 0: must stay out of the way of the Left paddle"); }
+        other => { panic!("expected an invalid arena, got {:?}", other) }
+    }
+}
+
+#[test]
+fn shipped_arenas_are_valid() {
+    // Dimensions of the piston frontend
+    let mut cfg = CFG.clone();
+    cfg.area = Vector2::new(400000, 240000);
+    cfg.paddle = Vector2::new(8000, 30000);
+    cfg.ball_size = 8000;
+    assert_eq!(arena::load("res/arenas/pillars.toml", &cfg).unwrap().len(), 4);
+}
+
+#[test]
+fn ball_bounces_off_obstacles() {
+    let mut cfg = CFG.clone();
+    cfg.obstacles = vec![ObstacleConfiguration {
+        position: Vector2::new(6000, 6000),
+        size: Vector2::new(1000, 1000),
+        velocity: Vector2::new(0, 0),
+        travel: Vector2::new(0, 0)
+    }];
+    let mut game = Game::new(cfg);
+
+    let mut obstacles = Vec::new();
+    game.process(Action::Initialize, |e| {
+        if let Event::Create { id, entity: Entity::Obstacle, .. } = e {
+            obstacles.push(id);
+        }
+    }).unwrap();
+    assert_eq!(obstacles.len(), 1);
+
+    game.process(Action::Start, |_| {}).unwrap();
+    game.process(Action::Time { t: 20000 }, |_| {}).unwrap();
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    assert!(ball.velocity.y < 0);
+    assert!(ball.position.y < 5000);
+}
+
+#[test]
+fn moving_obstacles_stay_within_their_travel() {
+    let mut cfg = CFG.clone();
+    cfg.obstacles = vec![ObstacleConfiguration {
+        position: Vector2::new(-8000, 0),
+        size: Vector2::new(500, 500),
+        velocity: Vector2::new(0, 100),
+        travel: Vector2::new(0, 2000)
+    }];
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    let id = game.entities().into_iter().find(|e| e.entity == Entity::Obstacle).unwrap().id;
+    let mut ys = Vec::new();
+    for i in 1..60 {
+        game.process(Action::Time { t: i * 1000 }, |_| {}).unwrap();
+        ys.push(game.entity(id).unwrap().position.y);
+    }
+    assert_eq!(*ys.iter().max().unwrap(), 2000);
+    assert!(ys.iter().all(|&y| y >= -2000));
+    assert!(ys[ys.len() - 1] < 2000);
+}
+
+#[test]
+fn obstacles_keep_their_own_travel() {
+    let obstacle = |x| ObstacleConfiguration {
+        position: Vector2::new(x, 0),
+        size: Vector2::new(500, 500),
+        velocity: Vector2::new(0, 100),
+        travel: Vector2::new(0, 2000)
+    };
+    let mut cfg = CFG.clone();
+    cfg.obstacles = vec![obstacle(-8000), obstacle(8000)];
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    // The second obstacle goes back to its own place after the first is gone
+    let ids: Vec<ID> = game.entities().into_iter().filter(|e| e.entity == Entity::Obstacle).map(|e| e.id).collect();
+    game.destroy(ids[0], &mut |_| {});
+    game.process(Action::Time { t: 5000 }, |_| {}).unwrap();
+    game.process(Action::Reset { seed: 1 }, |_| {}).unwrap();
+    assert_eq!(game.entity(ids[1]).unwrap().position, Vector2::new(8000, 0));
+
+    // Spawned ones move within their travel too
+    let id = game.spawn_obstacle(obstacle(0), &mut |_| {});
+    game.process(Action::Start, |_| {}).unwrap();
+    for i in 1..60 {
+        game.process(Action::Time { t: 5000 + i * 1000 }, |_| {}).unwrap();
+        assert!(game.entity(id).unwrap().position.y.abs() <= 2000);
+    }
+}
@@ -0,0 +1,148 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use cgmath::{ Vector2 };
+use pong::*;
+use pong::arena::ArenaError;
+use common::CFG;
+
+fn breakout_cfg(bricks: Vec<BrickConfiguration>) -> GameConfiguration {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::Breakout { lives: 3 };
+    cfg.bricks = bricks;
+    cfg
+}
+
+fn brick(x: i64, y: i64, hit_points: u8) -> BrickConfiguration {
+    BrickConfiguration { position: Vector2::new(x, y), size: Vector2::new(1000, 1000), hit_points: hit_points }
+}
+
+// Keeps the paddle under the ball
+fn track_ball(game: &mut Game, t: u64) {
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    let paddle = game.entity(0).unwrap();
+    let direction = if ball.position.x > paddle.position.x + 300 {
+        Direction::Up
+    } else if ball.position.x < paddle.position.x - 300 {
+        Direction::Down
+    } else {
+        Direction::Neutral
+    };
+    game.process(Action::Move { t: t, player: Player::Bottom, direction: direction }, |_| {}).unwrap();
+}
+
+fn is_game_over(result: Result<(), Error>) -> bool {
+    match result {
+        Err(Error::GameOver) => { true }
+        _ => { false }
+    }
+}
+
+#[test]
+fn levels_lay_out_rows_of_bricks() {
+    let cfg = breakout_cfg(Vec::new());
+    let bricks = arena::parse_level("rows = [\"12\", \".3\"]", &cfg).unwrap();
+    let laid_out: Vec<(i64, i64, u8)> = bricks.iter().map(|b| (b.position.x, b.position.y, b.hit_points)).collect();
+    assert_eq!(laid_out, vec![(-10000, 8500, 1), (10000, 8500, 2), (10000, 7500, 3)]);
+    assert_eq!(bricks[0].size, Vector2::new(10000, 500));
+
+    let invalid = |text| match arena::parse_level(text, &cfg) {
+        Err(ArenaError::Invalid(reason)) => { reason }
+        other => { panic!("expected an invalid level, got {:?}", other) }
+    };
+    assert_eq!(invalid("rows = []"), "rows must be a non-empty array of strings");
+    assert_eq!(invalid("rows = [\"\"]"), "row 0: must not be empty");
+    assert_eq!(invalid("rows = [\"11\", \"1\"]"), "row 1: must be as long as the first row");
+    assert_eq!(invalid("rows = [\"1x\"]"), "row 0: unknown brick x");
+    assert_eq!(invalid("rows = [\"..\"]"), "levels need at least one brick");
+    assert_eq!(invalid("rows = [\"1\", \"1\", \"1\", \"1\", \"1\", \"1\", \"1\", \"1\", \"1\"]"),
+               "row 8: must stay clear of the center");
+}
+
+#[test]
+fn shipped_levels_are_valid() {
+    // Dimensions of the piston frontend
+    let mut cfg = breakout_cfg(Vec::new());
+    cfg.area = Vector2::new(400000, 240000);
+    cfg.paddle = Vector2::new(8000, 30000);
+    cfg.ball_size = 8000;
+    assert_eq!(arena::load_level("res/levels/wall.toml", &cfg).unwrap().len(), 45);
+}
+
+#[test]
+fn bricks_take_their_hit_points_to_destroy() {
+    let mut game = Game::new(breakout_cfg(vec![brick(4000, 4000, 2)]));
+    assert_eq!(game.players(), vec![Player::Bottom]);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    let mut destroyed = Vec::new();
+    let mut play = |game: &mut Game, t| game.process(Action::Time { t: t }, |e| {
+        if let Event::BrickDestroyed { id } = e {
+            destroyed.push(id);
+        }
+    }).unwrap();
+
+    // The first hit only bounces the ball back
+    play(&mut game, 20000);
+    let brick = game.entities().into_iter().find(|e| e.entity == Entity::Brick).unwrap();
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    assert!(ball.velocity.y < 0);
+
+    for i in 21..100000 {
+        if game.entity(brick.id).is_none() {
+            break;
+        }
+        track_ball(&mut game, (i - 1) * 1000);
+        play(&mut game, i * 1000);
+    }
+    assert!(game.entity(brick.id).is_none());
+    drop(play);
+    assert_eq!(destroyed, vec![brick.id]);
+
+    // Clearing the bricks ends the game until it is reset
+    assert_eq!(game.state(), State::Ready);
+    assert!(is_game_over(game.process(Action::Start, |_| {})));
+    let mut created = Vec::new();
+    game.process(Action::Reset { seed: 0 }, |e| {
+        if let Event::Create { entity, .. } = e {
+            created.push(entity);
+        }
+    }).unwrap();
+    assert_eq!(created, vec![Entity::Brick]);
+    game.process(Action::Start, |_| {}).unwrap();
+}
+
+#[test]
+fn bricks_without_hit_points_go_with_the_first_hit() {
+    let mut game = Game::new(breakout_cfg(vec![brick(4000, 4000, 0)]));
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    let mut destroyed = 0;
+    game.process(Action::Time { t: 20000 }, |e| if let Event::BrickDestroyed { .. } = e { destroyed += 1; }).unwrap();
+    assert_eq!(destroyed, 1);
+}
+
+#[test]
+fn losing_the_last_life_ends_the_game() {
+    let mut game = Game::new(breakout_cfg(vec![brick(-15000, 8000, 9)]));
+    game.process(Action::Initialize, |_| {}).unwrap();
+
+    let mut lives = Vec::new();
+    let mut t = 0;
+    while !is_game_over(game.process(Action::Start, |_| {})) && t < 10000000 {
+        while game.state() == State::Running {
+            t += 1000;
+            game.process(Action::Move { t: t, player: Player::Bottom, direction: Direction::Down }, |_| {}).unwrap();
+            game.process(Action::Time { t: t }, |e| {
+                if let Event::LifeLost { player, lives: left } = e {
+                    lives.push((player, left));
+                }
+            }).unwrap();
+        }
+    }
+    assert_eq!(lives, vec![(Player::Bottom, 2), (Player::Bottom, 1), (Player::Bottom, 0)]);
+    assert!(game.entity(0).is_some());
+}
@@ -0,0 +1,47 @@
+#![allow(dead_code)]
+
+use cgmath::{ Vector2 };
+use pong::*;
+
+pub const CFG: GameConfiguration = GameConfiguration {
+    area: Vector2 {
+        x: 20000,
+        y: 10000,
+    },
+    paddle: Vector2 {
+        x: 1000,
+        y: 3000,
+    },
+    ball_size: 1000,
+    goal_opening: None,
+    walls: REFLECTING_WALLS,
+    mode: Mode::Classic,
+    multiball: None,
+    power_ups: None,
+    field: None,
+    paddle_physics: None,
+    paddle_zone: None,
+    abilities: Vec::new(),
+    handicaps: Vec::new(),
+    difficulty: None,
+    tennis: None,
+    obstacles: Vec::new(),
+    bricks: Vec::new()
+};
+
+pub fn initialized(cfg: GameConfiguration) -> Game {
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game
+}
+
+pub fn started(cfg: GameConfiguration) -> Game {
+    let mut game = initialized(cfg);
+    game.process(Action::Start, |_| {}).unwrap();
+    game
+}
+
+// The first entity of the kind
+pub fn entity(game: &Game, kind: Entity) -> EntityView {
+    game.entities().into_iter().find(|e| e.entity == kind).unwrap()
+}
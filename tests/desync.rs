@@ -0,0 +1,62 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+use pong::desync::{ DesyncDetector, Snapshot };
+
+fn started_game() -> Game {
+    let mut game = Game::new(CFG.clone());
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    game
+}
+
+#[test]
+fn identical_games_hash_identically() {
+    let mut a = started_game();
+    let mut b = started_game();
+    let mut detector = DesyncDetector::new();
+    for i in 1..50 {
+        a.process(Action::Time { t: i * 1000 }, |_| {}).unwrap();
+        b.process(Action::Time { t: i * 1000 }, |_| {}).unwrap();
+        assert_eq!(a.state_hash(), b.state_hash());
+        detector.record_local(Snapshot::of(&a));
+        detector.record_remote(Snapshot::of(&b));
+        assert!(detector.check().is_none());
+    }
+}
+
+#[test]
+fn pending_inputs_do_not_change_the_hash() {
+    let mut a = started_game();
+    let mut b = started_game();
+    a.process(Action::Move { t: 20000, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    a.process(Action::Time { t: 10500 }, |_| {}).unwrap();
+    b.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(a.state_hash(), b.state_hash());
+}
+
+#[test]
+fn first_diverging_tick_is_reported() {
+    let mut a = started_game();
+    let mut b = started_game();
+    let mut detector = DesyncDetector::new();
+    for i in 1..20 {
+        if i == 10 {
+            let move_action = Action::Move { t: 9000, player: Player::Left, direction: Direction::Up };
+            b.process(move_action, |_| {}).unwrap();
+        }
+        a.process(Action::Time { t: i * 1000 }, |_| {}).unwrap();
+        b.process(Action::Time { t: i * 1000 }, |_| {}).unwrap();
+        detector.record_local(Snapshot::of(&a));
+        detector.record_remote(Snapshot::of(&b));
+    }
+
+    let desync = detector.check().unwrap();
+    assert_eq!(desync.tick, 10);
+    assert!(desync.local.hash != desync.remote.hash);
+    assert!(desync.local.state.unwrap() != desync.remote.state.unwrap());
+}
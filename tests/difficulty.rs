@@ -0,0 +1,74 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use cgmath::Vector2;
+use pong::*;
+use common::{ CFG, entity };
+
+const DIFFICULTY: DifficultyConfiguration = DifficultyConfiguration {
+    shrink: 100,
+    rubber_band: 200,
+    min_scale: 500,
+    max_scale: 1500
+};
+
+fn resizes(events: &[Event]) -> Vec<(ID, i64, i64)> {
+    events.iter().filter_map(|e| match *e {
+        Event::Resize { id, w, h } => { Some((id, w, h)) }
+        _ => { None }
+    }).collect()
+}
+
+#[test]
+fn paddles_shrink_for_the_leader_and_grow_for_the_trailer() {
+    let mut cfg = CFG.clone();
+    cfg.difficulty = Some(DIFFICULTY);
+    let mut game = Game::new(cfg);
+    let mut events = Vec::new();
+    game.process(Action::Initialize, |e| events.push(e)).unwrap();
+    assert!(resizes(&events).is_empty());
+
+    // The left paddle gets out of the way so that the right player scores
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Down }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    let mut events = Vec::new();
+    game.process(Action::Time { t: 200000 }, |e| events.push(e)).unwrap();
+    assert_eq!(game.score(Player::Right), 1);
+
+    let (left, right) = (entity(&game, Entity::LeftPaddle), entity(&game, Entity::RightPaddle));
+    assert_eq!(left.size, Vector2::new(CFG.paddle.x, 3600));
+    assert_eq!(right.size, Vector2::new(CFG.paddle.x, 2700));
+    let mut resized = resizes(&events);
+    resized.sort();
+    let mut expected = vec![(left.id, CFG.paddle.x, 3600), (right.id, CFG.paddle.x, 2700)];
+    expected.sort();
+    assert_eq!(resized, expected);
+
+    // Both are back to normal once the scores are reset
+    let mut events = Vec::new();
+    game.process(Action::Reset { seed: 0 }, |e| events.push(e)).unwrap();
+    assert_eq!(resizes(&events).len(), 2);
+    assert_eq!(entity(&game, Entity::LeftPaddle).size, CFG.paddle);
+    assert_eq!(entity(&game, Entity::RightPaddle).size, CFG.paddle);
+}
+
+#[test]
+fn paddles_stay_within_their_scale() {
+    let mut cfg = CFG.clone();
+    cfg.difficulty = Some(DIFFICULTY);
+    cfg.handicaps = vec![HandicapConfiguration {
+        player: Player::Right,
+        paddle: None,
+        paddle_speed: None,
+        starting_score: 10
+    }];
+    let mut game = Game::new(cfg);
+    let mut events = Vec::new();
+    game.process(Action::Initialize, |e| events.push(e)).unwrap();
+    assert_eq!(resizes(&events).len(), 2);
+    assert_eq!(entity(&game, Entity::LeftPaddle).size.y, CFG.paddle.y * 3 / 2);
+    assert_eq!(entity(&game, Entity::RightPaddle).size.y, CFG.paddle.y / 2);
+}
@@ -0,0 +1,54 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+
+const FORWARD_OFFSET: i64 = 2000;
+
+fn doubles_game() -> Game {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::Doubles { forward_offset: FORWARD_OFFSET };
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game
+}
+
+#[test]
+fn each_side_has_a_forward_paddle() {
+    let game = doubles_game();
+    assert_eq!(game.players(), vec![Player::Left, Player::Right, Player::LeftForward, Player::RightForward]);
+
+    let mut left_x: Vec<i64> = game.entities().into_iter()
+        .filter(|e| e.entity == Entity::LeftPaddle)
+        .map(|e| e.position.x)
+        .collect();
+    left_x.sort();
+    assert_eq!(left_x, vec![CFG.paddle.x - CFG.area.x, CFG.paddle.x - CFG.area.x + FORWARD_OFFSET]);
+}
+
+#[test]
+fn forwards_return_the_ball_and_share_the_score() {
+    let mut game = doubles_game();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    // The ball comes off the top wall into the right forward, before the back
+    // paddle could reach it
+    let mut ball_x = Vec::new();
+    let mut goals = Vec::new();
+    game.process(Action::Time { t: 1000000 }, |e| match e {
+        Event::Move { id: 4, x, .. } => { ball_x.push(x); }
+        Event::Goal { player, score } => { goals.push((player, score.points)); }
+        _ => {}
+    }).unwrap();
+
+    let turn = ball_x.windows(2).position(|w| w[1] < w[0]).unwrap();
+    assert!(ball_x[turn] < CFG.area.x - 2 * CFG.paddle.x - CFG.ball_size);
+
+    let (player, score) = goals[0];
+    assert_eq!(game.score(player), score);
+    let teammate = if player == Player::Left { Player::LeftForward } else { Player::RightForward };
+    assert_eq!(game.score(teammate), score);
+}
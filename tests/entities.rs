@@ -0,0 +1,51 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use cgmath::{ Vector2 };
+use pong::*;
+use common::CFG;
+use pong::entities::EntityData;
+
+fn ball() -> EntityData {
+    EntityData {
+        kind: Entity::Ball,
+        size: Vector2::new(1000, 1000),
+        position: Vector2::new(0, 5000),
+        velocity: Vector2::new(-100, 0)
+    }
+}
+
+#[test]
+fn spawned_entities_get_fresh_ids() {
+    let mut game = Game::new(CFG.clone());
+    let mut events = Vec::new();
+    let early = game.spawn(ball(), &mut |e| events.push(e));
+    assert!(events.is_empty());
+
+    game.process(Action::Initialize, |e| events.push(e)).unwrap();
+    let created: Vec<ID> = events.iter().filter_map(|e| match e {
+        &Event::Create { id, .. } => { Some(id) }
+        _ => { None }
+    }).collect();
+    assert_eq!(created, vec![0, 1, 2, early]);
+
+    events.clear();
+    let late = game.spawn(ball(), &mut |e| events.push(e));
+    assert!(late > early);
+    match events[..] {
+        [Event::Create { id, entity: Entity::Ball, x: 0, y: 5000 }] => { assert_eq!(id, late); }
+        _ => { panic!("expected a single Create event") }
+    }
+
+    events.clear();
+    game.destroy(early, &mut |e| events.push(e));
+    game.destroy(early, &mut |e| events.push(e));
+    match events[..] {
+        [Event::Destroy { id }] => { assert_eq!(id, early); }
+        _ => { panic!("expected a single Destroy event") }
+    }
+    assert!(game.entity(early).is_none());
+    assert!(game.entity(late).is_some());
+}
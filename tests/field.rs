@@ -0,0 +1,66 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use cgmath::{ Vector2 };
+use pong::*;
+use common::{ CFG, initialized, entity };
+
+fn game_with_field(field: Option<FieldConfiguration>) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.field = field;
+    initialized(cfg)
+}
+
+#[test]
+fn gravity_adds_up_without_fractions() {
+    let mut game = game_with_field(Some(FieldConfiguration {
+        gravity: Vector2::new(0, -333),
+        wind: None,
+        curve: 0
+    }));
+    game.process(Action::Start, |_| {}).unwrap();
+
+    game.process(Action::Time { t: 30000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).velocity, Vector2::new(240, 240 - 333 * 30 / 1000));
+}
+
+#[test]
+fn wind_gusts_alternate() {
+    let mut game = game_with_field(Some(FieldConfiguration {
+        gravity: Vector2::new(0, 0),
+        wind: Some(WindConfiguration { force: Vector2::new(0, 1000), period: 20, duration: 10 }),
+        curve: 0
+    }));
+    game.process(Action::Start, |_| {}).unwrap();
+
+    // Ticks 1 to 9 blow up and ticks 20 to 29 blow down
+    game.process(Action::Time { t: 15000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).velocity.y, 249);
+    game.process(Action::Time { t: 30000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).velocity.y, 239);
+}
+
+#[test]
+fn moving_paddles_curve_the_ball() {
+    // The ball comes off the top wall into the left paddle, which is moving up
+    let play = |curve| {
+        let mut game = game_with_field(Some(FieldConfiguration {
+            gravity: Vector2::new(0, 0),
+            wind: None,
+            curve: curve
+        }));
+        game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+        game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+        game.process(Action::Start, |_| {}).unwrap();
+        game.process(Action::Time { t: 80000 }, |_| {}).unwrap();
+        entity(&game, Entity::Ball)
+    };
+
+    let straight = play(0);
+    let curved = play(1000);
+    assert!(straight.velocity.x > 0);
+    assert_eq!(curved.velocity.x, straight.velocity.x);
+    assert!(curved.velocity.y > straight.velocity.y);
+}
@@ -0,0 +1,93 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::{ CFG, initialized, entity };
+
+fn four_player_game(lives: u8) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::FourPlayer { lives: lives };
+    initialized(cfg)
+}
+
+#[test]
+fn horizontal_paddles_defend_top_and_bottom() {
+    let mut game = four_player_game(3);
+    assert_eq!(game.players(), vec![Player::Left, Player::Right, Player::Top, Player::Bottom]);
+
+    let top = entity(&game, Entity::TopPaddle);
+    assert_eq!(top.size.x, CFG.paddle.y);
+    assert_eq!(top.position.y, CFG.area.y - CFG.paddle.x);
+
+    game.process(Action::Move { t: 0, player: Player::Top, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    game.process(Action::Time { t: 5000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::TopPaddle).position.x, 5 * 300);
+}
+
+#[test]
+fn goals_cost_lives_until_the_wall_closes() {
+    let mut game = four_player_game(1);
+    let mut lost = Vec::new();
+    let mut destroyed = Vec::new();
+    let mut t = 0;
+    while lost.len() < 2 {
+        game.process(Action::Start, |_| {}).unwrap();
+        t += 100000;
+        game.process(Action::Time { t: t }, |e| match e {
+            Event::LifeLost { player, lives } => { lost.push((player, lives)); }
+            Event::Destroy { id } => { destroyed.push(id); }
+            Event::Goal { .. } => { panic!("nobody scores in four player mode") }
+            _ => {}
+        }).unwrap();
+    }
+
+    // Every conceding player had their only life and was removed from the game
+    assert_eq!(destroyed.len(), 2);
+    for &(player, lives) in lost.iter() {
+        assert_eq!(lives, 0);
+        assert_eq!(game.lives(player), 0);
+    }
+    assert!(lost[0].0 != lost[1].0);
+    let paddles = game.entities().into_iter().filter(|e| e.entity != Entity::Ball).count();
+    assert_eq!(paddles, 2);
+
+    let mut created = 0;
+    game.process(Action::Reset { seed: 0 }, |e| if let Event::Create { .. } = e { created += 1; }).unwrap();
+    assert_eq!(created, 2);
+    assert_eq!(game.lives(lost[0].0), 1);
+}
+
+#[test]
+fn the_last_player_standing_wins() {
+    let mut game = four_player_game(1);
+    let mut t = 0;
+    let mut rounds = 0;
+    loop {
+        match game.process(Action::Start, |_| {}) {
+            Ok(()) => {}
+            Err(Error::GameOver) => { break; }
+            r => { panic!("expected GameOver, got {:?}", r) }
+        }
+        rounds += 1;
+        assert!(rounds < 50, "the game never finished");
+        t += 100000;
+        game.process(Action::Time { t: t }, |_| {}).unwrap();
+    }
+
+    let standing: Vec<Player> = game.players().into_iter().filter(|&player| game.lives(player) > 0).collect();
+    assert_eq!(standing.len(), 1);
+    let paddles = game.entities().into_iter().filter(|e| e.entity != Entity::Ball).count();
+    assert_eq!(paddles, 1);
+}
+
+#[test]
+fn classic_games_have_no_top_and_bottom_players() {
+    let mut game = initialized(CFG.clone());
+    match game.process(Action::Move { t: 0, player: Player::Top, direction: Direction::Up }, |_| {}) {
+        Err(Error::UnknownPlayer) => {}
+        r => { panic!("expected UnknownPlayer, got {:?}", r) }
+    }
+}
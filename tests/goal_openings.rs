@@ -0,0 +1,51 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+
+// Serves the ball left with the left paddle out of the way, so that it reaches
+// the wall at y = 4000
+fn serve_past_left_paddle(goal_opening: Option<i64>) -> (Game, Vec<Player>) {
+    let mut cfg = CFG.clone();
+    cfg.goal_opening = goal_opening;
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Down }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    let mut goals = Vec::new();
+    game.process(Action::Time { t: 100000 }, |e| {
+        if let Event::Goal { player, .. } = e {
+            goals.push(player);
+        }
+    }).unwrap();
+    (game, goals)
+}
+
+#[test]
+fn whole_wall_is_the_goal_by_default() {
+    let (game, goals) = serve_past_left_paddle(None);
+    assert_eq!(goals, vec![Player::Right]);
+    assert_eq!(game.state(), State::Ready);
+}
+
+#[test]
+fn walls_beside_the_opening_reflect_the_ball() {
+    let (game, goals) = serve_past_left_paddle(Some(2000));
+    assert!(goals.is_empty());
+    assert_eq!(game.state(), State::Running);
+
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    assert!(ball.velocity.x > 0);
+    assert!(ball.position.x >= -CFG.area.x);
+}
+
+#[test]
+fn ball_scores_through_the_opening() {
+    let (_, goals) = serve_past_left_paddle(Some(5000));
+    assert_eq!(goals, vec![Player::Right]);
+}
@@ -0,0 +1,59 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use cgmath::Vector2;
+use pong::*;
+use common::{ CFG, started, entity };
+
+fn handicap(player: Player) -> HandicapConfiguration {
+    HandicapConfiguration { player: player, paddle: None, paddle_speed: None, starting_score: 0 }
+}
+
+#[test]
+fn paddles_can_be_sized_per_player() {
+    let mut cfg = CFG.clone();
+    cfg.handicaps = vec![HandicapConfiguration { paddle: Some(Vector2::new(500, 1500)), ..handicap(Player::Left) }];
+    let game = started(cfg);
+    let (left, right) = (entity(&game, Entity::LeftPaddle), entity(&game, Entity::RightPaddle));
+    assert_eq!(left.size, Vector2::new(500, 1500));
+    assert_eq!(left.position.x, 500 - CFG.area.x);
+    assert_eq!(right.size, CFG.paddle);
+    assert_eq!(right.position.x, CFG.area.x - CFG.paddle.x);
+}
+
+#[test]
+fn paddles_can_be_faster_or_slower_per_player() {
+    let mut cfg = CFG.clone();
+    cfg.handicaps = vec![HandicapConfiguration { paddle_speed: Some(600), ..handicap(Player::Left) }];
+    let mut game = started(cfg);
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Right, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Time { t: 5000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 3000);
+    assert_eq!(entity(&game, Entity::RightPaddle).position.y, 1500);
+
+    // With paddle physics the handicap replaces the top speed
+    let mut cfg = CFG.clone();
+    cfg.paddle_physics = Some(PaddlePhysicsConfiguration { acceleration: 50, max_speed: 400, friction: 20 });
+    cfg.handicaps = vec![HandicapConfiguration { paddle_speed: Some(200), ..handicap(Player::Left) }];
+    let mut game = started(cfg);
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Right, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).velocity.y, 200);
+    assert_eq!(entity(&game, Entity::RightPaddle).velocity.y, 400);
+}
+
+#[test]
+fn players_can_start_ahead() {
+    let mut cfg = CFG.clone();
+    cfg.handicaps = vec![HandicapConfiguration { starting_score: 3, ..handicap(Player::Right) }];
+    let mut game = started(cfg);
+    assert_eq!(game.score(Player::Right), 3);
+    assert_eq!(game.score(Player::Left), 0);
+
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    assert_eq!(game.score(Player::Right), 3);
+}
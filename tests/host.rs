@@ -0,0 +1,90 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+use pong::host::Host;
+
+fn move_action(player: Player) -> Action {
+    Action::Move { t: 0, player: player, direction: Direction::Up }
+}
+
+#[test]
+fn peers_only_control_their_own_paddle() {
+    let mut host = Host::new(CFG.clone(), 20);
+    assert_eq!(host.join(10), Some(Player::Left));
+    assert_eq!(host.join(11), Some(Player::Right));
+    assert_eq!(host.join(12), None);
+    host.process(10, Action::Initialize, |_| {}).unwrap();
+
+    assert!(host.process(10, move_action(Player::Left), |_| {}).is_ok());
+    assert!(host.process(11, move_action(Player::Right), |_| {}).is_ok());
+    match host.process(11, move_action(Player::Left), |_| {}) {
+        Err(Error::WrongPlayer) => {}
+        r => { panic!("expected WrongPlayer, got {:?}", r) }
+    }
+    match host.process(12, move_action(Player::Right), |_| {}) {
+        Err(Error::WrongPlayer) => {}
+        r => { panic!("expected WrongPlayer, got {:?}", r) }
+    }
+}
+
+#[test]
+fn only_host_controls_the_game() {
+    let mut host = Host::new(CFG.clone(), 20);
+    host.join(10);
+    host.join(11);
+    match host.process(11, Action::Initialize, |_| {}) {
+        Err(Error::NotHost) => {}
+        r => { panic!("expected NotHost, got {:?}", r) }
+    }
+    host.process(10, Action::Initialize, |_| {}).unwrap();
+    match host.process(11, Action::Reset { seed: 0 }, |_| {}) {
+        Err(Error::NotHost) => {}
+        r => { panic!("expected NotHost, got {:?}", r) }
+    }
+
+    host.leave(10);
+    assert!(host.is_host(11));
+    assert!(host.process(11, Action::Start, |_| {}).is_ok());
+}
+
+#[test]
+fn inputs_are_rate_limited() {
+    let mut host = Host::new(CFG.clone(), 5);
+    host.join(10);
+    host.process(10, Action::Initialize, |_| {}).unwrap();
+    for _ in 0..5 {
+        host.process(10, move_action(Player::Left), |_| {}).unwrap();
+    }
+    match host.process(10, move_action(Player::Left), |_| {}) {
+        Err(Error::RateLimited) => {}
+        r => { panic!("expected RateLimited, got {:?}", r) }
+    }
+}
+
+#[test]
+fn times_and_inputs_stay_close_to_the_game_time() {
+    let mut host = Host::new(CFG.clone(), 20);
+    host.join(10);
+    host.process(10, Action::Initialize, |_| {}).unwrap();
+    host.process(10, Action::Start, |_| {}).unwrap();
+    host.process(10, Action::Time { t: 5500 }, |_| {}).unwrap();
+    match host.process(10, Action::Time { t: 5200 }, |_| {}) {
+        Err(Error::TimeOutOfRange) => {}
+        r => { panic!("expected TimeOutOfRange, got {:?}", r) }
+    }
+    match host.process(10, Action::Time { t: std::u64::MAX }, |_| {}) {
+        Err(Error::TimeOutOfRange) => {}
+        r => { panic!("expected TimeOutOfRange, got {:?}", r) }
+    }
+
+    let far = Action::Move { t: std::u64::MAX, player: Player::Left, direction: Direction::Up };
+    match host.process(10, far, |_| {}) {
+        Err(Error::TimeOutOfRange) => {}
+        r => { panic!("expected TimeOutOfRange, got {:?}", r) }
+    }
+    host.process(10, move_action(Player::Left), |_| {}).unwrap();
+}
@@ -0,0 +1,40 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+
+// Plays the same timestamped inputs while advancing time in steps of frame_step
+fn play(frame_step: u64) -> Game {
+    let mut game = Game::new(CFG.clone());
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    let inputs = [(3500, Player::Left, Direction::Up),
+                  (7200, Player::Right, Direction::Down),
+                  (15000, Player::Left, Direction::Neutral)];
+    let mut next_input = 0;
+    let mut t = 0;
+    while t < 30000 {
+        t = if t + frame_step > 30000 { 30000 } else { t + frame_step };
+        while next_input < inputs.len() && inputs[next_input].0 <= t {
+            let (input_t, player, direction) = inputs[next_input];
+            game.process(Action::Move { t: input_t, player: player, direction: direction }, |_| {}).unwrap();
+            next_input += 1;
+        }
+        game.process(Action::Time { t: t }, |_| {}).unwrap();
+    }
+    game
+}
+
+#[test]
+fn results_do_not_depend_on_frame_rate() {
+    let reference = play(1000);
+    for &frame_step in [700, 1700, 4000, 16667].iter() {
+        let game = play(frame_step);
+        assert_eq!(game.tick(), reference.tick());
+        assert_eq!(game.state_hash(), reference.state_hash(), "frame step {}", frame_step);
+    }
+}
@@ -0,0 +1,198 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use cgmath::Vector2;
+use pong::*;
+use common::CFG;
+
+// A mode as a downstream crate would write it: the left player keeps the ball
+// in play against the other walls until they run out of lives
+#[derive(Debug)]
+struct Survival {
+    rounds: Rc<Cell<u32>>,
+    hits: Rc<Cell<u32>>
+}
+
+impl GameMode for Survival {
+    fn players(&self) -> Vec<Player> {
+        vec![Player::Left]
+    }
+
+    fn starting_lives(&self) -> u8 {
+        2
+    }
+
+    fn on_start(&mut self, _game: &mut Game, _callback: &mut dyn FnMut(Event)) {
+        self.rounds.set(self.rounds.get() + 1);
+    }
+
+    fn on_goal(&mut self, game: &mut Game, _ball: ID, player: Player, callback: &mut dyn FnMut(Event)) -> bool {
+        game.lose_life(player, callback);
+        true
+    }
+
+    fn on_hit(&mut self, game: &mut Game, _ball: ID, player: Player, callback: &mut dyn FnMut(Event)) {
+        self.hits.set(self.hits.get() + 1);
+        game.score_point(player, callback);
+    }
+
+    fn is_finished(&self, game: &Game) -> bool {
+        game.lives(Player::Left) == 0
+    }
+}
+
+fn survival_game() -> (Game, Rc<Cell<u32>>, Rc<Cell<u32>>) {
+    let (rounds, hits) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+    let mode = Survival { rounds: rounds.clone(), hits: hits.clone() };
+    let mut game = Game::with_mode(CFG.clone(), Box::new(mode));
+    game.process(Action::Initialize, |_| {}).unwrap();
+    (game, rounds, hits)
+}
+
+// Moves the left paddle towards the ball, or away from it
+fn steer(game: &mut Game, t: u64, towards: bool) {
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    let paddle = game.entity(0).unwrap();
+    let direction = match (ball.position.y > paddle.position.y, towards) {
+        (true, true) | (false, false) => { Direction::Up }
+        _ => { Direction::Down }
+    };
+    game.process(Action::Move { t: t, player: Player::Left, direction: direction }, |_| {}).unwrap();
+}
+
+#[test]
+fn custom_modes_choose_the_players() {
+    let (game, _, _) = survival_game();
+    assert_eq!(game.players(), vec![Player::Left]);
+    assert_eq!(game.lives(Player::Left), 2);
+    assert_eq!(game.entities().into_iter().filter(|e| e.entity == Entity::RightPaddle).count(), 0);
+}
+
+#[test]
+fn custom_modes_score_hits() {
+    let (mut game, _, hits) = survival_game();
+    game.process(Action::Start, |_| {}).unwrap();
+    let mut goals = Vec::new();
+    for i in 1..20000 {
+        steer(&mut game, (i - 1) * 1000, true);
+        game.process(Action::Time { t: i * 1000 }, |e| {
+            if let Event::Goal { player, score } = e {
+                goals.push((player, score.points));
+            }
+        }).unwrap();
+    }
+    assert!(hits.get() > 1);
+    assert_eq!(goals.len() as u32, hits.get());
+    assert_eq!(game.score(Player::Left) as u32, hits.get());
+}
+
+#[test]
+fn custom_modes_finish_the_game() {
+    let (mut game, rounds, _) = survival_game();
+    let mut lives = Vec::new();
+    let mut t = 0;
+    while !game.process(Action::Start, |_| {}).is_err() && t < 10000000 {
+        while game.state() == State::Running {
+            steer(&mut game, t, false);
+            t += 1000;
+            game.process(Action::Time { t: t }, |e| {
+                if let Event::LifeLost { lives: left, .. } = e {
+                    lives.push(left);
+                }
+            }).unwrap();
+        }
+    }
+    assert_eq!(lives, vec![1, 0]);
+    assert_eq!(rounds.get(), 2);
+
+    match game.process(Action::Start, |_| {}) {
+        Err(Error::GameOver) => {}
+        other => { panic!("expected the game to be over, got {:?}", other) }
+    }
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    assert_eq!(game.lives(Player::Left), 2);
+    game.process(Action::Start, |_| {}).unwrap();
+    assert_eq!(rounds.get(), 3);
+}
+
+// Serves from the center at a random angle, and takes a point away for every miss
+#[derive(Debug)]
+struct Penalty;
+
+impl GameMode for Penalty {
+    fn on_start(&mut self, game: &mut Game, callback: &mut dyn FnMut(Event)) {
+        let vertical = game.rng().range(-100, 101);
+        let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+        game.serve(ball.id, Vector2::new(0, 0), Vector2::new(300, vertical), callback);
+    }
+
+    fn on_goal(&mut self, game: &mut Game, _ball: ID, player: Player, _callback: &mut dyn FnMut(Event)) -> bool {
+        let score = game.score(player);
+        game.set_score(player, score.saturating_sub(1));
+        true
+    }
+}
+
+#[test]
+fn custom_modes_serve_and_set_scores() {
+    let mut cfg = CFG.clone();
+    cfg.handicaps = vec![HandicapConfiguration { player: Player::Right, paddle: None, paddle_speed: None, starting_score: 2 }];
+    let mut game = Game::with_mode(cfg, Box::new(Penalty));
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Reset { seed: 3 }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    assert_eq!(ball.velocity.x, 300);
+    assert!(ball.velocity.y.abs() <= 100);
+
+    game.process(Action::Move { t: 0, player: Player::Right, direction: Direction::Down }, |_| {}).unwrap();
+    let mut t = 0;
+    while game.state() == State::Running && t < 1000000 {
+        t += 1000;
+        game.process(Action::Time { t: t }, |_| {}).unwrap();
+    }
+    assert_eq!(game.score(Player::Right), 1);
+}
+
+// Takes missed balls out of play, and tries to start over from inside its hooks
+#[derive(Debug)]
+struct Meddling {
+    results: Rc<Cell<u32>>
+}
+
+impl GameMode for Meddling {
+    fn on_goal(&mut self, game: &mut Game, ball: ID, _player: Player, callback: &mut dyn FnMut(Event)) -> bool {
+        game.destroy(ball, callback);
+        true
+    }
+
+    fn on_hit(&mut self, game: &mut Game, _ball: ID, _player: Player, _callback: &mut dyn FnMut(Event)) {
+        for action in vec![Action::Reset { seed: 0 }, Action::Start] {
+            if let Err(Error::InModeHook) = game.process(action, |_| {}) {
+                self.results.set(self.results.get() + 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn hooks_can_destroy_balls_but_not_process_actions() {
+    let results = Rc::new(Cell::new(0));
+    let mut game = Game::with_mode(CFG.clone(), Box::new(Meddling { results: results.clone() }));
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    let mut t = 0;
+    while game.state() == State::Running && t < 1000000 {
+        steer(&mut game, t, t < 300000);
+        t += 1000;
+        game.process(Action::Time { t: t }, |_| {}).unwrap();
+    }
+    assert!(results.get() >= 2);
+    assert_eq!(game.state(), State::Ready);
+    assert!(game.entity(ball.id).is_none());
+}
@@ -0,0 +1,92 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::{ CFG, initialized, started, entity };
+
+#[test]
+fn paddles_stop_at_their_target() {
+    let mut game = started(CFG.clone());
+    game.process(Action::MoveTo { t: 0, player: Player::Left, y: 1000 }, |_| {}).unwrap();
+    game.process(Action::Time { t: 3000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 900);
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 1000);
+    assert_eq!(entity(&game, Entity::LeftPaddle).velocity.y, 0);
+
+    // Targets out of reach leave the paddle against the wall
+    game.process(Action::MoveTo { t: 10000, player: Player::Left, y: -CFG.area.y }, |_| {}).unwrap();
+    game.process(Action::Time { t: 60000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, CFG.paddle.y - CFG.area.y);
+}
+
+#[test]
+fn other_inputs_take_over_from_the_target() {
+    let mut game = started(CFG.clone());
+    game.process(Action::MoveTo { t: 0, player: Player::Left, y: 5000 }, |_| {}).unwrap();
+    game.process(Action::Move { t: 2000, player: Player::Left, direction: Direction::Neutral }, |_| {}).unwrap();
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 600);
+}
+
+#[test]
+fn horizontal_paddles_take_the_target_as_x() {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::FourPlayer { lives: 3 };
+    let mut game = started(cfg);
+    game.process(Action::MoveTo { t: 0, player: Player::Top, y: -2000 }, |_| {}).unwrap();
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::TopPaddle).position.x, -2000);
+    assert_eq!(entity(&game, Entity::TopPaddle).position.y, CFG.area.y - CFG.paddle.x);
+}
+
+#[test]
+fn paddles_with_physics_settle_on_their_target() {
+    let mut cfg = CFG.clone();
+    cfg.paddle_physics = Some(PaddlePhysicsConfiguration { acceleration: 50, max_speed: 400, friction: 20 });
+    let mut game = started(cfg);
+    game.process(Action::MoveTo { t: 0, player: Player::Left, y: 4321 }, |_| {}).unwrap();
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert!(entity(&game, Entity::LeftPaddle).velocity.y <= 400);
+    game.process(Action::Time { t: 100000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 4321);
+    assert_eq!(entity(&game, Entity::LeftPaddle).velocity.y, 0);
+}
+
+#[test]
+fn targets_out_of_the_area_are_clamped() {
+    let mut game = started(CFG.clone());
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::MoveTo { t: 1000, player: Player::Left, y: i64::min_value() }, |_| {}).unwrap();
+    game.process(Action::Time { t: 100000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, CFG.paddle.y - CFG.area.y);
+
+    game.process(Action::MoveTo { t: 100000, player: Player::Left, y: i64::max_value() }, |_| {}).unwrap();
+    game.process(Action::Time { t: 200000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, CFG.area.y - CFG.paddle.y);
+}
+
+#[test]
+fn targets_follow_the_paddle_size() {
+    let mut cfg = CFG.clone();
+    cfg.difficulty = Some(DifficultyConfiguration { shrink: 100, rubber_band: 200, min_scale: 500, max_scale: 1500 });
+    let mut game = initialized(cfg);
+
+    // The right player scores, after which their paddle is smaller and the left one larger
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Down }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    game.process(Action::Time { t: 200000 }, |_| {}).unwrap();
+    assert_eq!(game.score(Player::Right), 1);
+
+    game.process(Action::MoveTo { t: 200000, player: Player::Left, y: i64::max_value() }, |_| {}).unwrap();
+    game.process(Action::MoveTo { t: 200000, player: Player::Right, y: i64::min_value() }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    game.process(Action::Time { t: 300000 }, |_| {}).unwrap();
+    let (left, right) = (entity(&game, Entity::LeftPaddle), entity(&game, Entity::RightPaddle));
+    assert_eq!((left.position.y, left.velocity.y), (CFG.area.y - left.size.y, 0));
+    assert_eq!((right.position.y, right.velocity.y), (right.size.y - CFG.area.y, 0));
+    assert!(right.size.y < CFG.paddle.y && left.size.y > CFG.paddle.y);
+}
@@ -0,0 +1,86 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use std::collections::HashSet;
+use pong::*;
+use common::CFG;
+
+fn multiball_game(interval: u64, score_interval: u8) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.multiball = Some(MultiballConfiguration {
+        interval: interval,
+        score_interval: score_interval,
+        max_balls: 3
+    });
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    game
+}
+
+#[test]
+fn extra_balls_spawn_on_interval_up_to_limit() {
+    let mut game = multiball_game(10, 0);
+    let mut created = Vec::new();
+    game.process(Action::Time { t: 45000 }, |e| if let Event::Create { id, entity: Entity::Ball, .. } = e {
+        created.push(id);
+    }).unwrap();
+
+    assert_eq!(created.len(), 2);
+    let balls = game.entities().into_iter().filter(|e| e.entity == Entity::Ball).count();
+    assert_eq!(balls, 3);
+}
+
+#[test]
+fn extra_balls_are_destroyed_when_they_score() {
+    let mut game = multiball_game(10, 0);
+    let mut alive = HashSet::new();
+    let mut destroyed = 0;
+    let mut goals = 0;
+    for e in game.entities() {
+        alive.insert(e.id);
+    }
+    game.process(Action::Time { t: 1000000 }, |e| match e {
+        Event::Create { id, .. } => { assert!(alive.insert(id)); }
+        Event::Destroy { id } => {
+            assert!(alive.remove(&id));
+            destroyed += 1;
+        }
+        Event::Move { id, .. } => { assert!(alive.contains(&id)); }
+        Event::Goal { .. } => { goals += 1; }
+        _ => {}
+    }).unwrap();
+
+    assert!(destroyed > 0);
+    assert!(goals >= destroyed);
+    assert_eq!(goals, (game.score(Player::Left) + game.score(Player::Right)) as usize);
+    assert_eq!(game.entities().len(), alive.len());
+}
+
+#[test]
+fn scores_spawn_extra_balls() {
+    let mut game = multiball_game(0, 1);
+    let mut created = 0;
+    game.process(Action::Time { t: 1000000 }, |e| if let Event::Create { .. } = e {
+        created += 1;
+    }).unwrap();
+
+    // The first goal ends the round and leaves a new ball waiting for the next one
+    assert_eq!(created, 1);
+    let balls = game.entities().into_iter().filter(|e| e.entity == Entity::Ball).count();
+    assert_eq!(balls, 2);
+}
+
+#[test]
+fn reset_goes_back_to_one_ball() {
+    let mut game = multiball_game(10, 0);
+    game.process(Action::Time { t: 45000 }, |_| {}).unwrap();
+    let mut destroyed = 0;
+    game.process(Action::Reset { seed: 1 }, |e| if let Event::Destroy { .. } = e { destroyed += 1; }).unwrap();
+    assert_eq!(destroyed, 2);
+    let balls: Vec<EntityView> = game.entities().into_iter().filter(|e| e.entity == Entity::Ball).collect();
+    assert_eq!(balls.len(), 1);
+    assert_eq!((balls[0].position.x, balls[0].position.y), (0, 0));
+}
@@ -0,0 +1,81 @@
+extern crate pong;
+extern crate cgmath;
+extern crate rustc_serialize;
+
+mod common;
+
+use rustc_serialize::json::Json;
+use pong::*;
+use pong::host::Host;
+use common::{ CFG, started, entity };
+
+const PHYSICS: PaddlePhysicsConfiguration = PaddlePhysicsConfiguration {
+    acceleration: 50,
+    max_speed: 400,
+    friction: 20
+};
+
+fn game(physics: Option<PaddlePhysicsConfiguration>) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.paddle_physics = physics;
+    started(cfg)
+}
+
+#[test]
+fn analog_input_scales_the_paddle_speed() {
+    let mut game = game(None);
+    game.process(Action::MoveAxis { t: 0, player: Player::Left, value: 0.5 }, |_| {}).unwrap();
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 10 * 150);
+
+    // Values past the ends of the axis count as the ends
+    game.process(Action::MoveAxis { t: 10000, player: Player::Left, value: -3.0 }, |_| {}).unwrap();
+    game.process(Action::Time { t: 15000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 10 * 150 - 5 * 300);
+}
+
+#[test]
+fn paddles_accelerate_and_coast_to_a_stop() {
+    let mut game = game(Some(PHYSICS));
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    // 50, 100, ... up to 400 per tick
+    assert_eq!(entity(&game, Entity::LeftPaddle).velocity.y, 400);
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 1800 + 2 * 400);
+
+    game.process(Action::Move { t: 10000, player: Player::Left, direction: Direction::Neutral }, |_| {}).unwrap();
+    game.process(Action::Time { t: 15000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).velocity.y, 300);
+    game.process(Action::Time { t: 40000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).velocity.y, 0);
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, 2600 + 3800);
+}
+
+#[test]
+fn analog_input_sets_the_top_speed() {
+    let mut game = game(Some(PHYSICS));
+    game.process(Action::MoveAxis { t: 0, player: Player::Left, value: -0.5 }, |_| {}).unwrap();
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).velocity.y, -200);
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.y, -(50 + 100 + 150 + 7 * 200));
+}
+
+#[test]
+fn peers_send_analog_input_for_their_own_paddle() {
+    let json = Json::from_str(r#"{ "action": "move_axis", "t": 0, "player": "right", "value": 0.25 }"#).unwrap();
+    let action = websocket::action_from_json(&json).unwrap();
+    match action {
+        Action::MoveAxis { t: 0, player: Player::Right, value } => { assert_eq!(value, 0.25); }
+        _ => { panic!("expected a move_axis action") }
+    }
+
+    let mut host = Host::new(CFG.clone(), 20);
+    host.join(10);
+    host.join(11);
+    host.process(10, Action::Initialize, |_| {}).unwrap();
+    match host.process(10, action, |_| {}) {
+        Err(Error::WrongPlayer) => {}
+        r => { panic!("expected WrongPlayer, got {:?}", r) }
+    }
+    assert!(host.process(11, Action::MoveAxis { t: 0, player: Player::Right, value: 0.25 }, |_| {}).is_ok());
+}
@@ -0,0 +1,82 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::{ CFG, started, entity };
+
+const ZONE: i64 = 4000;
+
+fn game(zone: Option<i64>) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.paddle_zone = zone;
+    started(cfg)
+}
+
+fn move_paddle(game: &mut Game, t: u64, player: Player, direction: Direction) {
+    game.process(Action::Move { t: t, player: player, direction: direction }, |_| {}).unwrap();
+}
+
+#[test]
+fn paddles_stay_on_their_line_without_a_zone() {
+    let mut game = game(None);
+    move_paddle(&mut game, 0, Player::Left, Direction::UpForward);
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    let paddle = entity(&game, Entity::LeftPaddle);
+    assert_eq!(paddle.position.x, CFG.paddle.x - CFG.area.x);
+    assert_eq!(paddle.position.y, 10 * 300);
+}
+
+#[test]
+fn paddles_move_forward_and_back_within_their_zone() {
+    let mut game = game(Some(ZONE));
+    let left_line = CFG.paddle.x - CFG.area.x;
+    let right_line = CFG.area.x - CFG.paddle.x;
+    move_paddle(&mut game, 0, Player::Left, Direction::UpForward);
+    move_paddle(&mut game, 0, Player::Right, Direction::Forward);
+    game.process(Action::Time { t: 10000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position, cgmath::Vector2::new(left_line + 3000, 3000));
+    assert_eq!(entity(&game, Entity::RightPaddle).position.x, right_line - 3000);
+
+    game.process(Action::Time { t: 30000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.x, left_line + ZONE);
+    assert_eq!(entity(&game, Entity::RightPaddle).position.x, right_line - ZONE);
+
+    move_paddle(&mut game, 30000, Player::Left, Direction::Back);
+    game.process(Action::Time { t: 60000 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::LeftPaddle).position.x, left_line);
+
+    // Reset puts the paddles back on their line
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::RightPaddle).position, cgmath::Vector2::new(right_line, 0));
+}
+
+#[test]
+fn paddles_moving_forward_push_the_ball() {
+    // The left paddle can reach the center, where the ball only moves up and down
+    let mut game = game(Some(CFG.area.x - CFG.paddle.x));
+    game.process(Action::Reset { seed: 1 }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    let mut hit = None;
+    for i in 1..200 {
+        let ball = entity(&game, Entity::Ball);
+        let paddle = entity(&game, Entity::LeftPaddle);
+        let direction = if ball.position.y > paddle.position.y + 300 {
+            Direction::UpForward
+        } else if ball.position.y < paddle.position.y - 300 {
+            Direction::DownForward
+        } else {
+            Direction::Forward
+        };
+        move_paddle(&mut game, (i - 1) * 1000, Player::Left, direction);
+        game.process(Action::Time { t: i * 1000 }, |_| {}).unwrap();
+        if entity(&game, Entity::Ball).velocity.x != 0 {
+            hit = Some(entity(&game, Entity::Ball));
+            break;
+        }
+    }
+    // Bounced off the paddle moving at 300 towards it
+    assert_eq!(hit.expect("the paddle should reach the ball").velocity.x, 600);
+}
@@ -0,0 +1,157 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+
+const POWER_UPS: PowerUpConfiguration = PowerUpConfiguration {
+    interval: 50,
+    duration: 2000,
+    max_power_ups: 4,
+    size: 2000
+};
+
+#[derive(Debug, PartialEq)]
+enum Logged {
+    Spawned(ID, PowerUp, i64, i64),
+    Collected(u64, ID, Player, PowerUp),
+    Expired(u64, Player, PowerUp)
+}
+
+// Both paddles follow the ball
+fn track_ball(game: &mut Game, t: u64) {
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    for &(player, id) in [(Player::Left, 0), (Player::Right, 1)].iter() {
+        let paddle = game.entity(id).unwrap();
+        let direction = if ball.position.y > paddle.position.y + 300 {
+            Direction::Up
+        } else if ball.position.y < paddle.position.y - 300 {
+            Direction::Down
+        } else {
+            Direction::Neutral
+        };
+        game.process(Action::Move { t: t, player: player, direction: direction }, |_| {}).unwrap();
+    }
+}
+
+// Plays from a reset with the given seed, starting a new round after every goal
+fn play(seed: i64, ticks: u64) -> (Game, Vec<Logged>) {
+    let mut cfg = CFG.clone();
+    cfg.power_ups = Some(POWER_UPS);
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Reset { seed: seed }, |_| {}).unwrap();
+
+    let mut log = Vec::new();
+    let mut created = Vec::new();
+    for i in 1..ticks + 1 {
+        if game.state() == State::Ready {
+            game.process(Action::Start, |_| {}).unwrap();
+        }
+        track_ball(&mut game, (i - 1) * 1000);
+        let mut events = Vec::new();
+        game.process(Action::Time { t: i * 1000 }, |e| events.push(e)).unwrap();
+        for e in events {
+            match e {
+                Event::Create { id, x, y, .. } => {
+                    created.push((id, x, y));
+                }
+                Event::PowerUpSpawned { id, power_up } => {
+                    let &(_, x, y) = created.iter().find(|c| c.0 == id).unwrap();
+                    log.push(Logged::Spawned(id, power_up, x, y));
+                }
+                Event::PowerUpCollected { id, player, power_up } => {
+                    log.push(Logged::Collected(game.tick(), id, player, power_up));
+                }
+                Event::PowerUpExpired { player, power_up } => {
+                    log.push(Logged::Expired(game.tick(), player, power_up));
+                }
+                _ => {}
+            }
+        }
+    }
+    (game, log)
+}
+
+#[test]
+fn spawns_follow_the_seed() {
+    let (_, a) = play(0, 2000);
+    let (_, b) = play(0, 2000);
+    let (_, c) = play(-2, 2000);
+    assert!(!a.is_empty());
+    assert_eq!(a, b);
+    assert!(a != c);
+
+    for logged in a.iter() {
+        if let &Logged::Spawned(_, _, x, y) = logged {
+            assert!(x.abs() <= CFG.area.x / 2);
+            assert!(y.abs() <= CFG.area.y - POWER_UPS.size);
+        }
+    }
+}
+
+#[test]
+fn collected_power_ups_expire_after_their_duration() {
+    let (game, log) = play(0, 20000);
+
+    let collected: Vec<&Logged> = log.iter().filter(|l| match l { &&Logged::Collected(..) => true, _ => false }).collect();
+    assert!(!collected.is_empty());
+    for c in collected {
+        if let &Logged::Collected(tick, id, player, power_up) = c {
+            assert!(game.entity(id).is_none());
+            let expires = tick + POWER_UPS.duration;
+            if expires <= game.tick() {
+                assert!(log.contains(&Logged::Expired(expires, player, power_up)));
+            }
+        }
+    }
+}
+
+#[test]
+fn grow_and_shrink_resize_paddles_until_reset() {
+    let mut cfg = CFG.clone();
+    cfg.power_ups = Some(POWER_UPS);
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+
+    let mut resized = 0;
+    let mut t = 0;
+    while resized < 2 && t < 100000000 {
+        if game.state() == State::Ready {
+            game.process(Action::Start, |_| {}).unwrap();
+        }
+        track_ball(&mut game, t);
+        t += 1000;
+        let mut collected = Vec::new();
+        game.process(Action::Time { t: t }, |e| {
+            if let Event::PowerUpCollected { player, power_up, .. } = e {
+                collected.push((player, power_up));
+            }
+        }).unwrap();
+
+        for (player, power_up) in collected {
+            let (own, other) = if player == Player::Left { (0, 1) } else { (1, 0) };
+            let size = |id| game.entity(id).unwrap().size.y;
+            match power_up {
+                PowerUp::Grow => {
+                    assert!(size(own) > CFG.paddle.y);
+                    resized += 1;
+                }
+                PowerUp::Shrink => {
+                    assert!(size(other) < CFG.paddle.y);
+                    resized += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    assert_eq!(resized, 2);
+
+    // Resetting ends all effects
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    assert_eq!(game.entity(0).unwrap().size, CFG.paddle);
+    assert_eq!(game.entity(1).unwrap().size, CFG.paddle);
+}
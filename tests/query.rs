@@ -0,0 +1,66 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use std::collections::HashMap;
+use cgmath::{ Vector2 };
+use pong::*;
+use common::CFG;
+
+#[test]
+fn views_match_events() {
+    let mut game = Game::new(CFG.clone());
+    let mut positions = HashMap::new();
+    let mut goals = Vec::new();
+    {
+        let mut handle = |e| match e {
+            Event::Create { id, x, y, .. } | Event::Move { id, x, y } => { positions.insert(id, (x, y)); }
+            Event::Goal { player, score } => { goals.push((player, score.points)); }
+            _ => {}
+        };
+        game.process(Action::Initialize, &mut handle).unwrap();
+        assert_eq!(game.state(), State::Ready);
+        game.process(Action::Start, &mut handle).unwrap();
+        assert_eq!(game.state(), State::Running);
+        game.process(Action::Time { t: 1000000 }, &mut handle).unwrap();
+    }
+
+    assert_eq!(game.entities().len(), 3);
+    for e in game.entities() {
+        assert_eq!(positions[&e.id], (e.position.x, e.position.y));
+    }
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    assert_eq!(ball.size, Vector2::new(CFG.ball_size, CFG.ball_size));
+    assert_eq!(game.entity(ball.id).unwrap().velocity, ball.velocity);
+    assert!(game.entity(100).is_none());
+
+    let (player, score) = goals[0];
+    assert_eq!(game.score(player), score);
+    assert_eq!(game.state(), State::Ready);
+    assert!(game.tick() > 0);
+}
+
+#[test]
+fn only_moving_entities_send_moves() {
+    let mut game = Game::new(CFG.clone());
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    let mut moved = Vec::new();
+    game.process(Action::Time { t: 1000 }, |e| if let Event::Move { id, .. } = e { moved.push(id) }).unwrap();
+    assert_eq!(moved, vec![ball.id]);
+}
+
+#[test]
+fn serves_send_moves() {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::Practice { pattern: ServePattern::Straight, speed: 300 };
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    let mut moves = Vec::new();
+    game.process(Action::Start, |e| if let Event::Move { id, x, y } = e { moves.push((id, x, y)) }).unwrap();
+    let ball = game.entities().into_iter().find(|e| e.entity == Entity::Ball).unwrap();
+    assert_eq!(moves, vec![(ball.id, ball.position.x, ball.position.y)]);
+    assert_eq!(ball.position.x, CFG.area.x - CFG.ball_size);
+}
@@ -0,0 +1,78 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::{ CFG, initialized, entity };
+
+fn single_player_game(mode: Mode) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.mode = mode;
+    initialized(cfg)
+}
+
+// Moves the left paddle towards the ball every tick, or to the other half of
+// the wall to miss it
+fn play<F>(game: &mut Game, ticks: u64, miss: bool, mut callback: F) where F: FnMut(Event) {
+    let t = game.tick() * 1000;
+    for i in 0..ticks {
+        if game.state() == State::Ready {
+            break;
+        }
+        let (ball, paddle) = (entity(game, Entity::Ball).position.y, game.entity(0).unwrap().position.y);
+        let target = if miss { -ball } else { ball };
+        let direction = if target > paddle { Direction::Up } else { Direction::Down };
+        game.process(Action::Move { t: t + i * 1000, player: Player::Left, direction: direction }, |_| {}).unwrap();
+        game.process(Action::Time { t: t + (i + 1) * 1000 }, &mut callback).unwrap();
+    }
+}
+
+#[test]
+fn squash_scores_consecutive_returns_until_a_miss() {
+    let mut game = single_player_game(Mode::Squash);
+    assert_eq!(game.players(), vec![Player::Left]);
+    game.process(Action::Start, |_| {}).unwrap();
+
+    let mut points = Vec::new();
+    play(&mut game, 1000, false, |e| if let Event::Goal { player, score } = e { points.push((player, score.points)); });
+    assert!(points.len() >= 3);
+    for (i, &(player, score)) in points.iter().enumerate() {
+        assert_eq!(player, Player::Left);
+        assert_eq!(score as usize, i + 1);
+    }
+    assert_eq!(game.state(), State::Running);
+
+    let mut ended = Vec::new();
+    play(&mut game, 1000, true, |e| if let Event::StreakEnded { player, streak } = e { ended.push((player, streak)); });
+    assert_eq!(ended.len(), 1);
+    assert!(ended[0].1 as usize >= points.len());
+    assert_eq!(game.score(Player::Left), 0);
+    assert_eq!(game.state(), State::Ready);
+}
+
+#[test]
+fn practice_launcher_serves_the_pattern() {
+    let mut game = single_player_game(Mode::Practice { pattern: ServePattern::Alternating, speed: 300 });
+    game.process(Action::Start, |_| {}).unwrap();
+    assert_eq!(entity(&game, Entity::Ball).position.x, CFG.area.x - CFG.ball_size);
+    assert_eq!(entity(&game, Entity::Ball).velocity, cgmath::Vector2::new(-300, 100));
+
+    // Missed serves come again from the launcher, the other way
+    play(&mut game, 200, true, |_| {});
+    assert_eq!(game.state(), State::Running);
+    assert_eq!(game.score(Player::Left), 0);
+    assert_eq!(entity(&game, Entity::Ball).velocity.x, -300);
+    assert!(entity(&game, Entity::Ball).velocity.y != 100);
+}
+
+#[test]
+fn practice_returns_score() {
+    let mut game = single_player_game(Mode::Practice { pattern: ServePattern::Straight, speed: 300 });
+    game.process(Action::Start, |_| {}).unwrap();
+
+    let mut points = 0;
+    play(&mut game, 3000, false, |e| if let Event::Goal { .. } = e { points += 1; });
+    assert!(points >= 5);
+    assert_eq!(game.score(Player::Left), points);
+}
@@ -0,0 +1,118 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+
+const L: Player = Player::Left;
+const R: Player = Player::Right;
+
+// Gives away a point a tick in the given order
+#[derive(Debug)]
+struct Scripted {
+    points: Vec<Player>
+}
+
+impl GameMode for Scripted {
+    fn on_tick(&mut self, game: &mut Game, callback: &mut dyn FnMut(Event)) {
+        if !self.points.is_empty() {
+            game.score_point(self.points.remove(0), callback);
+        }
+    }
+}
+
+// Odd seeds serve straight up and down, so that the ball never scores itself
+fn play(tennis: TennisConfiguration, points: &[Player]) -> (Game, Vec<(Player, ScoreReport)>) {
+    let mut cfg = CFG.clone();
+    cfg.tennis = Some(tennis);
+    let mut game = Game::with_mode(cfg, Box::new(Scripted { points: points.to_vec() }));
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Reset { seed: 1 }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    let mut reports = Vec::new();
+    game.process(Action::Time { t: points.len() as u64 * 1000 }, |e| {
+        if let Event::Goal { player, score } = e {
+            reports.push((player, score));
+        }
+    }).unwrap();
+    (game, reports)
+}
+
+fn tennis(games: u8, sets: u8, tiebreak_points: u8) -> TennisConfiguration {
+    TennisConfiguration { games: games, sets: sets, tiebreak_points: tiebreak_points }
+}
+
+#[test]
+fn games_go_through_deuce_and_advantage() {
+    let (game, reports) = play(tennis(6, 2, 7), &[L, R, L]);
+    assert_eq!((game.call(L), game.call(R)), ("30".to_string(), "15".to_string()));
+    assert_eq!(reports[2].1, ScoreReport { points: 2, games: 0, sets: 0, won: Won::Point });
+
+    let (game, _) = play(tennis(6, 2, 7), &[L, R, L, R, L, R]);
+    assert_eq!((game.call(L), game.call(R)), ("40".to_string(), "40".to_string()));
+    let (game, _) = play(tennis(6, 2, 7), &[L, R, L, R, L, R, L]);
+    assert_eq!((game.call(L), game.call(R)), ("AD".to_string(), "40".to_string()));
+    let (game, _) = play(tennis(6, 2, 7), &[L, R, L, R, L, R, L, R]);
+    assert_eq!((game.call(L), game.call(R)), ("40".to_string(), "40".to_string()));
+
+    let (game, reports) = play(tennis(6, 2, 7), &[L, R, L, R, L, R, L, R, L, L]);
+    assert_eq!(reports.last().unwrap().1, ScoreReport { points: 0, games: 1, sets: 0, won: Won::Game });
+    assert_eq!((game.call(L), game.call(R)), ("0".to_string(), "0".to_string()));
+    assert_eq!((game.games(L), game.games(R)), (1, 0));
+}
+
+#[test]
+fn sets_are_won_two_games_ahead() {
+    let game_for = |player| vec![player; 4];
+    let points: Vec<Player> = [L, R, L, L].iter().flat_map(|&player| game_for(player)).collect();
+    let (game, reports) = play(tennis(2, 2, 0), &points);
+    let won: Vec<Won> = reports.iter().map(|&(_, score)| score.won).filter(|&won| won != Won::Point).collect();
+    assert_eq!(won, vec![Won::Game, Won::Game, Won::Game, Won::Set]);
+    assert_eq!((game.sets(L), game.games(L), game.games(R)), (1, 0, 0));
+    assert_eq!(game.state(), State::Running);
+}
+
+#[test]
+fn tiebreaks_decide_the_match() {
+    let game_for = |player| vec![player; 4];
+    let mut points: Vec<Player> = [L, R, L, R].iter().flat_map(|&player| game_for(player)).collect();
+    points.extend_from_slice(&[R, L, L, L]);
+    let (mut game, reports) = play(tennis(2, 1, 3), &points);
+
+    // The tiebreak is counted in plain points
+    assert_eq!(reports[reports.len() - 2].1, ScoreReport { points: 2, games: 2, sets: 0, won: Won::Point });
+    assert_eq!(reports.last().unwrap().1, ScoreReport { points: 0, games: 0, sets: 1, won: Won::Match });
+    match game.process(Action::Start, |_| {}) {
+        Err(Error::GameOver) => {}
+        _ => { panic!("the match should be over"); }
+    }
+
+    let (game, _) = play(tennis(2, 1, 3), &points[..points.len() - 1]);
+    assert_eq!((game.call(L), game.call(R)), ("2".to_string(), "1".to_string()));
+}
+
+#[test]
+fn long_deuces_keep_going_back_to_deuce() {
+    let mut points: Vec<Player> = (0..300).map(|k| if k % 2 == 0 { L } else { R }).collect();
+    let (game, _) = play(tennis(6, 2, 7), &points);
+    assert_eq!((game.call(L), game.call(R)), ("40".to_string(), "40".to_string()));
+
+    points.extend_from_slice(&[L, L]);
+    let (game, reports) = play(tennis(6, 2, 7), &points);
+    assert_eq!(reports.last().unwrap().1, ScoreReport { points: 0, games: 1, sets: 0, won: Won::Game });
+    assert_eq!(game.games(L), 1);
+}
+
+#[test]
+fn tiebreaks_keep_counting_past_the_tie() {
+    let game_for = |player| vec![player; 4];
+    let mut points: Vec<Player> = [L, R, L, R].iter().flat_map(|&player| game_for(player)).collect();
+    points.extend_from_slice(&[L, R, L, R, L, R, L]);
+    let (game, reports) = play(tennis(2, 1, 3), &points);
+
+    let tiebreak: Vec<(Player, u8)> = reports[16..].iter().map(|&(player, score)| (player, score.points)).collect();
+    assert_eq!(tiebreak, vec![(L, 1), (R, 1), (L, 2), (R, 2), (L, 3), (R, 3), (L, 4)]);
+    assert_eq!((game.call(L), game.call(R)), ("4".to_string(), "3".to_string()));
+}
@@ -0,0 +1,71 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::CFG;
+
+// Reset with seed 0 serves at the left player, who gets out of the way so that
+// the right player scores, and odd seeds serve straight up and down
+fn game(duration: u64, seed: i64) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::Timed { duration: duration };
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Reset { seed: seed }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Down }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+    game
+}
+
+fn is_over(game: &mut Game) -> bool {
+    match game.process(Action::Start, |_| {}) {
+        Err(Error::GameOver) => { true }
+        _ => { false }
+    }
+}
+
+#[test]
+fn the_clock_counts_down_every_second_of_play() {
+    let mut cfg = CFG.clone();
+    cfg.mode = Mode::Timed { duration: 3000000 };
+    let mut game = Game::new(cfg);
+    game.process(Action::Initialize, |_| {}).unwrap();
+    game.process(Action::Reset { seed: 1 }, |_| {}).unwrap();
+
+    // The clock stands still until the round starts
+    game.process(Action::Time { t: 5000000 }, |_| {}).unwrap();
+    let mut remaining = Vec::new();
+    game.process(Action::Start, |e| if let Event::ClockTick { remaining: r } = e { remaining.push(r) }).unwrap();
+    game.process(Action::Time { t: 10000000 }, |e| if let Event::ClockTick { remaining: r } = e { remaining.push(r) }).unwrap();
+    assert_eq!(remaining, vec![3000000, 2000000, 1000000, 0]);
+}
+
+#[test]
+fn the_higher_score_wins_when_time_runs_out() {
+    let mut game = game(100000, 0);
+    game.process(Action::Time { t: 100000 }, |_| {}).unwrap();
+    assert_eq!(game.score(Player::Right), 1);
+    assert_eq!(game.state(), State::Ready);
+
+    game.process(Action::Start, |_| {}).unwrap();
+    game.process(Action::Time { t: 200000 }, |_| {}).unwrap();
+    assert_eq!(game.score(Player::Right), 1);
+    assert!(is_over(&mut game));
+}
+
+#[test]
+fn ties_go_to_the_next_point() {
+    let mut game = game(50000, 0);
+    game.process(Action::Time { t: 60000 }, |_| {}).unwrap();
+    assert_eq!(game.state(), State::Running);
+
+    game.process(Action::Time { t: 200000 }, |_| {}).unwrap();
+    assert_eq!(game.score(Player::Right), 1);
+    assert!(is_over(&mut game));
+
+    // Reset starts the clock over
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    assert!(!is_over(&mut game));
+}
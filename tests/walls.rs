@@ -0,0 +1,61 @@
+extern crate pong;
+extern crate cgmath;
+
+mod common;
+
+use pong::*;
+use common::{ CFG, initialized, entity };
+
+fn game_with_walls(top: WallBehaviour, bottom: WallBehaviour) -> Game {
+    let mut cfg = CFG.clone();
+    cfg.walls.top = top;
+    cfg.walls.bottom = bottom;
+    initialized(cfg)
+}
+
+#[test]
+fn wrapping_walls_bring_the_ball_back_from_the_other_side() {
+    let mut game = game_with_walls(WallBehaviour::Wrap, WallBehaviour::Wrap);
+    game.process(Action::Start, |_| {}).unwrap();
+
+    // The ball leaves through the top wall after 42 ticks
+    game.process(Action::Time { t: 45000 }, |_| {}).unwrap();
+    let ball = entity(&game, Entity::Ball);
+    assert_eq!(ball.position.y, 45 * 240 - 2 * CFG.area.y);
+    assert!(ball.velocity.y > 0);
+}
+
+#[test]
+fn absorbing_walls_end_the_rally() {
+    let mut game = game_with_walls(WallBehaviour::Absorb, WallBehaviour::Absorb);
+    game.process(Action::Start, |_| {}).unwrap();
+
+    // Nobody has hit the ball yet, so nobody scores
+    let mut goals = 0;
+    game.process(Action::Time { t: 45000 }, |e| {
+        if let Event::Goal { .. } = e {
+            goals += 1;
+        }
+    }).unwrap();
+    assert_eq!(goals, 0);
+    assert_eq!(game.state(), State::Ready);
+    assert_eq!(entity(&game, Entity::Ball).position.y, 0);
+}
+
+#[test]
+fn player_hitting_the_ball_into_an_absorbing_wall_concedes() {
+    let mut game = game_with_walls(WallBehaviour::Reflect, WallBehaviour::Absorb);
+    game.process(Action::Reset { seed: 0 }, |_| {}).unwrap();
+    game.process(Action::Move { t: 0, player: Player::Left, direction: Direction::Up }, |_| {}).unwrap();
+    game.process(Action::Start, |_| {}).unwrap();
+
+    // The ball comes off the top wall into the left paddle and down into the bottom wall
+    let mut goals = Vec::new();
+    game.process(Action::Time { t: 200000 }, |e| {
+        if let Event::Goal { player, score } = e {
+            goals.push((player, score.points));
+        }
+    }).unwrap();
+    assert_eq!(goals, vec![(Player::Right, 1)]);
+    assert_eq!(game.state(), State::Ready);
+}
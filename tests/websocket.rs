@@ -0,0 +1,94 @@
+extern crate pong;
+extern crate cgmath;
+extern crate ws;
+extern crate rustc_serialize;
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+use rustc_serialize::json::Json;
+use pong::*;
+use common::CFG;
+
+struct Client {
+    out: ws::Sender,
+    t: u64,
+    received: Rc<RefCell<Vec<Json>>>
+}
+
+impl Client {
+    fn send_time(&mut self) -> ws::Result<()> {
+        self.t += 1000;
+        self.out.send(format!(r#"{{ "action": "time", "t": {} }}"#, self.t))
+    }
+}
+
+impl ws::Handler for Client {
+    fn on_open(&mut self, _: ws::Handshake) -> ws::Result<()> {
+        try!(self.out.send(r#"{ "action": "initialize" }"#));
+        try!(self.out.send(r#"{ "action": "start" }"#));
+        try!(self.out.send(r#"{ "action": "move", "t": 0, "player": "left", "direction": "down" }"#));
+        self.send_time()
+    }
+
+    // Advance the game one frame at a time, after the ball has moved in the previous one
+    fn on_message(&mut self, msg: ws::Message) -> ws::Result<()> {
+        let json = Json::from_str(msg.as_text().unwrap()).unwrap();
+        let event = json.find("event").and_then(|e| e.as_string()).map(|e| e.to_string());
+        let ball_moved = event == Some("move".to_string())
+            && json.find("id").and_then(|id| id.as_u64()) == Some(2);
+        self.received.borrow_mut().push(json);
+
+        if event == Some("goal".to_string()) || self.t > 1000000 {
+            self.out.close(ws::CloseCode::Normal)
+        } else if ball_moved {
+            self.send_time()
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn scripted_rally() {
+    let (tx, rx) = mpsc::channel();
+    let server = thread::spawn(move || {
+        let server = websocket::server(CFG.clone()).unwrap().bind("127.0.0.1:0").unwrap();
+        tx.send((server.local_addr().unwrap(), server.broadcaster())).unwrap();
+        server.run().unwrap();
+    });
+    let (addr, broadcaster) = rx.recv().unwrap();
+
+    let received = Rc::new(RefCell::new(Vec::new()));
+    ws::connect(format!("ws://{}", addr), |out| {
+        Client { out: out, t: 0, received: received.clone() }
+    }).unwrap();
+
+    broadcaster.shutdown().unwrap();
+    server.join().unwrap();
+
+    let received = received.borrow();
+    let events: Vec<&str> = received.iter()
+        .map(|json| json.find("event").and_then(|e| e.as_string()).unwrap())
+        .collect();
+    assert_eq!(&events[..5], &["joined", "create", "create", "create", "round_start"]);
+    assert_eq!(events.iter().filter(|&&e| e == "goal").count(), 1);
+
+    // The ball must have come back off the right paddle before the goal, after
+    // which it is reset to the center
+    let goal = received.iter()
+        .position(|json| json.find("event").and_then(|e| e.as_string()) == Some("goal"))
+        .unwrap();
+    let ball_x: Vec<i64> = received[..goal].iter()
+        .filter(|json| json.find("event").and_then(|e| e.as_string()) == Some("move")
+                && json.find("id").and_then(|id| id.as_u64()) == Some(2))
+        .map(|json| json.find("x").and_then(|x| x.as_i64()).unwrap())
+        .collect();
+    assert!(ball_x.windows(3).any(|w| w[1] > 0 && w[0] < w[1] && w[2] < w[1]));
+
+    let goal = &received[goal];
+    assert_eq!(goal.find("score").and_then(|s| s.as_u64()), Some(1));
+}